@@ -6,8 +6,9 @@ use std::fs::File;
 // An internal enum to track the origin of the input, making the reader's state explicit.
 enum InputSource {
     Terminal,
+    // `echo` prints each line as it is read, for scripts run from the command line
     #[allow(dead_code)] 
-    File { reader: BufReader<File>, path: String },
+    File { reader: BufReader<File>, path: String, echo: bool },
 }
 
 // The internal buffer for storing the current line of input.
@@ -35,6 +36,15 @@ impl Reader {
     
     // Initialization Method 2 (File Input)
     pub fn new_from_file(path: &str) -> Result<Self, io::Error> {
+        Self::open_file(path, true)
+    }
+
+    // File input that isn't echoed, e.g. for modules loaded by `import`
+    pub fn new_from_file_quiet(path: &str) -> Result<Self, io::Error> {
+        Self::open_file(path, false)
+    }
+
+    fn open_file(path: &str, echo: bool) -> Result<Self, io::Error> {
         // Open the file and create a buffered reader for incremental reading
         let file = File::open(path)?;
         let buf_reader = BufReader::new(file);
@@ -43,7 +53,7 @@ impl Reader {
             char_buffer: Vec::new(),
             line_position: 0,
             line_number: 0,
-            source: InputSource::File { reader: buf_reader, path: path.to_string() },
+            source: InputSource::File { reader: buf_reader, path: path.to_string(), echo },
             at_eof: false,
         })
     }
@@ -72,7 +82,7 @@ impl Reader {
                 Ok(true)
             }
             // From file input
-            InputSource::File { reader, .. } => {
+            InputSource::File { reader, echo, .. } => {
                 let mut line = String::new();
                 let n = reader.read_line(&mut line)?;
                 if n == 0 {
@@ -86,6 +96,9 @@ impl Reader {
                 self.char_buffer = normalized.chars().collect();
                 self.line_position = 0;
                 self.line_number += 1;
+                if !*echo {
+                    return Ok(true);
+                }
 
                 // Echo the file line being read so file-mode behaves like the REPL.
                 // Ensure we always emit a terminating newline even if the input
@@ -98,7 +111,7 @@ impl Reader {
                     // Normalized line lacked a newline (likely the file's last
                     // line). Emit one so subsequent println!() calls start on
                     // the next line.
-                    print!("\n");
+                    println!();
                 }
                 io::stdout().flush().ok();
 
@@ -18,27 +18,34 @@ pub struct Scanner {
 impl Scanner {
     // Inits
     pub fn new_from_terminal() -> Self {
-        let scanner = Scanner {
+        Scanner {
             source: Reader::new_from_terminal(),
             line_number: 0,
             at_eof: false,
             next_token_cache: Token::new_token(TokenType::Eof, "".to_string(), None, 0),
             next_token_loaded: false,
             errors: Vec::new(),
-        };
-        scanner
+        }
     }
 
     pub fn new_from_file(path: &str) -> Result<Self, io::Error> {
-        let scanner = Scanner {
-            source: Reader::new_from_file(path)?,
+        Ok(Self::from_reader(Reader::new_from_file(path)?))
+    }
+
+    // Scan a file without echoing its lines, for modules loaded by `import`
+    pub fn new_from_file_quiet(path: &str) -> Result<Self, io::Error> {
+        Ok(Self::from_reader(Reader::new_from_file_quiet(path)?))
+    }
+
+    fn from_reader(source: Reader) -> Self {
+        Scanner {
+            source,
             line_number: 0,
             at_eof: false,
             next_token_cache: Token::new_token(TokenType::Eof, "".to_string(), None, 0),
             next_token_loaded: false,
             errors: Vec::new(),
-        };
-        Ok(scanner)
+        }
     }
 
     // Methods
@@ -48,12 +55,16 @@ impl Scanner {
             ')' => Some(Token::new_token(TokenType::RightParen, ")".to_string(), None, self.source.get_line_number())),
             '{' => Some(Token::new_token(TokenType::LeftBrace, "{".to_string(), None, self.source.get_line_number())),
             '}' => Some(Token::new_token(TokenType::RightBrace, "}".to_string(), None, self.source.get_line_number())),
+            '[' => Some(Token::new_token(TokenType::LeftBracket, "[".to_string(), None, self.source.get_line_number())),
+            ']' => Some(Token::new_token(TokenType::RightBracket, "]".to_string(), None, self.source.get_line_number())),
             ',' => Some(Token::new_token(TokenType::Comma, ",".to_string(), None, self.source.get_line_number())),
             '.' => Some(Token::new_token(TokenType::Dot, ".".to_string(), None, self.source.get_line_number())),
-            '-' => Some(Token::new_token(TokenType::Minus, "-".to_string(), None, self.source.get_line_number())),
-            '+' => Some(Token::new_token(TokenType::Plus, "+".to_string(), None, self.source.get_line_number())),
+            ':' => Some(Token::new_token(TokenType::Colon, ":".to_string(), None, self.source.get_line_number())),
             ';' => Some(Token::new_token(TokenType::Semicolon, ";".to_string(), None, self.source.get_line_number())),
-            '*' => Some(Token::new_token(TokenType::Star, "*".to_string(), None, self.source.get_line_number())),
+            '&' => Some(Token::new_token(TokenType::Ampersand, "&".to_string(), None, self.source.get_line_number())),
+            '|' => Some(Token::new_token(TokenType::Pipe, "|".to_string(), None, self.source.get_line_number())),
+            '^' => Some(Token::new_token(TokenType::Caret, "^".to_string(), None, self.source.get_line_number())),
+            '~' => Some(Token::new_token(TokenType::Tilde, "~".to_string(), None, self.source.get_line_number())),
             _ => None,
         }
     }
@@ -64,13 +75,30 @@ impl Scanner {
             ('<', Some('=')) => Some(Token::new_token(TokenType::LessEqual, "<=".to_string(), None, self.source.get_line_number())),
             ('>', Some('=')) => Some(Token::new_token(TokenType::GreaterEqual, ">=".to_string(), None, self.source.get_line_number())),
             ('=', Some('=')) => Some(Token::new_token(TokenType::EqualEqual, "==".to_string(), None, self.source.get_line_number())),
+            ('=', Some('>')) => Some(Token::new_token(TokenType::FatArrow, "=>".to_string(), None, self.source.get_line_number())),
             ('!', Some('=')) => Some(Token::new_token(TokenType::BangEqual, "!=".to_string(), None, self.source.get_line_number())),
+            ('+', Some('=')) => Some(Token::new_token(TokenType::PlusEqual, "+=".to_string(), None, self.source.get_line_number())),
+            ('-', Some('=')) => Some(Token::new_token(TokenType::MinusEqual, "-=".to_string(), None, self.source.get_line_number())),
+            ('*', Some('=')) => Some(Token::new_token(TokenType::StarEqual, "*=".to_string(), None, self.source.get_line_number())),
+            ('%', Some('=')) => Some(Token::new_token(TokenType::PercentEqual, "%=".to_string(), None, self.source.get_line_number())),
+            ('+', Some('+')) => Some(Token::new_token(TokenType::PlusPlus, "++".to_string(), None, self.source.get_line_number())),
+            ('-', Some('-')) => Some(Token::new_token(TokenType::MinusMinus, "--".to_string(), None, self.source.get_line_number())),
+            ('*', Some('*')) => Some(Token::new_token(TokenType::StarStar, "**".to_string(), None, self.source.get_line_number())),
+            ('<', Some('<')) => Some(Token::new_token(TokenType::LessLess, "<<".to_string(), None, self.source.get_line_number())),
+            ('>', Some('>')) => Some(Token::new_token(TokenType::GreaterGreater, ">>".to_string(), None, self.source.get_line_number())),
+            ('?', Some('?')) => Some(Token::new_token(TokenType::QuestionQuestion, "??".to_string(), None, self.source.get_line_number())),
+            ('?', Some('.')) => Some(Token::new_token(TokenType::QuestionDot, "?.".to_string(), None, self.source.get_line_number())),
 
             // Check for singles after not doubles
             ('<', _) => Some(Token::new_token(TokenType::Less, "<".to_string(), None, self.source.get_line_number())),
             ('>', _) => Some(Token::new_token(TokenType::Greater, ">".to_string(), None, self.source.get_line_number())),
             ('=', _) => Some(Token::new_token(TokenType::Equal, "=".to_string(), None, self.source.get_line_number())),
             ('!', _) => Some(Token::new_token(TokenType::Bang, "!".to_string(), None, self.source.get_line_number())),
+            ('+', _) => Some(Token::new_token(TokenType::Plus, "+".to_string(), None, self.source.get_line_number())),
+            ('-', _) => Some(Token::new_token(TokenType::Minus, "-".to_string(), None, self.source.get_line_number())),
+            ('*', _) => Some(Token::new_token(TokenType::Star, "*".to_string(), None, self.source.get_line_number())),
+            ('%', _) => Some(Token::new_token(TokenType::Percent, "%".to_string(), None, self.source.get_line_number())),
+            ('?', _) => Some(Token::new_token(TokenType::Question, "?".to_string(), None, self.source.get_line_number())),
 
             _ => None,
         }
@@ -78,10 +106,7 @@ impl Scanner {
 
     // Helpers 
     pub fn is_white_space(c: char) -> bool {
-        match c {
-            ' ' | '\r' | '\t' | '\n' => true,
-            _ => false,
-        }
+        matches!(c, ' ' | '\r' | '\t' | '\n')
     }
     pub fn is_alpha(c: char) -> bool {
         (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || c == '_'
@@ -157,6 +182,20 @@ impl Scanner {
             return;
         }
 
+        // '...' (rest parameters). A lone '..' is kept as one Dot token with
+        // lexeme ".." so the parser reports "Unexpected '..'." rather than
+        // reading it as two dots.
+        if first_char == '.' && self.source.peek_char() == Some('.') {
+            self.source.next_char();
+            if self.source.peek_char() == Some('.') {
+                self.source.next_char();
+                self.next_token_cache = Token::new_token(TokenType::Ellipsis, "...".to_string(), None, self.source.get_line_number());
+            } else {
+                self.next_token_cache = Token::new_token(TokenType::Dot, "..".to_string(), None, self.source.get_line_number());
+            }
+            return;
+        }
+
         // Single token chars
         if let Some(single_char_token) = self.check_single_char_token(first_char) {
             // It's a single-character token
@@ -194,6 +233,11 @@ impl Scanner {
                     // After consuming the comment, load the next token
                     self.load_token();
                     return;
+                } else if second_char == '=' {
+                    // It's a '/=' compound assignment
+                    self.source.next_char(); // Consume the '='
+                    self.next_token_cache = Token::new_token(TokenType::SlashEqual, "/=".to_string(), None, self.source.get_line_number());
+                    return;
                 } else {
                     // It's a division token
                     self.next_token_cache = Token::new_token(TokenType::Slash, first_char.to_string(), None, self.source.get_line_number());
@@ -231,51 +275,7 @@ impl Scanner {
 
         // Number literals
         if Scanner::is_digit(first_char) {
-            let mut number_content = String::new();
-            number_content.push(first_char);
-
-            while let Some(c) = self.source.peek_char() {
-                if Scanner::is_digit(c) {
-                    number_content.push(c);
-                    self.source.next_char(); // Consume the digit
-                } else {
-                    break;
-                }
-            }
-
-            // Handle fractional part
-            if let Some('.') = self.source.peek_char() {
-                // Peek ahead to see if there's a digit after the dot
-                self.source.next_char(); // Consume the dot
-                if let Some(next_c) = self.source.peek_char() {
-                    if Scanner::is_digit(next_c) {
-                        number_content.push('.'); // Add the dot
-                    } else {
-                        // No digit after dot: record an error (invalid number)
-                        let msg = "Invalid number format".to_string();
-                        self.errors.push(msg.clone());
-                        crate::util::logger::global_logger().log(LogLevel::Debug, format!("scanner: {}", msg));
-                    }
-                }
-            }
-
-            // Get decimal digits after the dot
-            while let Some(c) = self.source.peek_char() {
-                if Scanner::is_digit(c) {
-                    number_content.push(c);
-                    self.source.next_char(); // Consume the digit
-                } else {
-                    break;
-                }
-            }
-
-            // let number_value = number_content.parse::<f64>();
-            // if number_value.is_err() {
-            //     logger.log(LogLevel::Error, "Invalid number format");
-            //     self.next_token_cache = Token::new_token(TokenType::Eof, "".to_string(), None, 0);
-            //     return;
-            // }
-            self.next_token_cache = Token::new_token(TokenType::Number, number_content, None, self.source.get_line_number());
+            self.next_token_cache = self.scan_number(first_char);
             return;
         }
 
@@ -312,6 +312,17 @@ impl Scanner {
                 "true" => TokenType::True,
                 "var" => TokenType::Var,
                 "while" => TokenType::While,
+                "throw" => TokenType::Throw,
+                "try" => TokenType::Try,
+                "catch" => TokenType::Catch,
+                "finally" => TokenType::Finally,
+                "div" => TokenType::Div,
+                "const" => TokenType::Const,
+                "in" => TokenType::In,
+                "yield" => TokenType::Yield,
+                "match" => TokenType::Match,
+                "import" => TokenType::Import,
+                "as" => TokenType::As,
                 _ => TokenType::Identifier,
             };
 
@@ -333,12 +344,157 @@ impl Scanner {
         Some(self.next_token_cache.clone())
     }
 
+    // Scan a numeric literal starting with `first_char`. Accepts decimal
+    // (`123`, `1.5`, `1.5e-3`), hex `0xFF`, binary `0b1010`, octal `0o17`,
+    // and `_` between digits. The value is stored as canonical text in
+    // Token::literal; malformed literals record an error and get no literal.
+    fn scan_number(&mut self, first_char: char) -> Token {
+        let mut lexeme = String::new();
+        lexeme.push(first_char);
+        self.take_number_word(&mut lexeme);
+
+        let is_radix = Scanner::has_radix_prefix(&lexeme);
+        if !is_radix {
+            // Fractional part; the word after the dot is validated below
+            if let Some('.') = self.source.peek_char() {
+                self.source.next_char();
+                lexeme.push('.');
+                self.take_number_word(&mut lexeme);
+            }
+        }
+
+        let result = if is_radix { Scanner::radix_literal(&lexeme) } else { Scanner::decimal_literal(&lexeme) };
+        match result {
+            Ok(literal) => Token::new_token(TokenType::Number, lexeme, Some(literal), self.source.get_line_number()),
+            Err(msg) => {
+                self.errors.push(msg.clone());
+                crate::util::logger::global_logger().log(LogLevel::Debug, format!("scanner: {}", msg));
+                Token::new_token(TokenType::Number, lexeme, None, self.source.get_line_number())
+            }
+        }
+    }
+
+    // Consume letters, digits and underscores. An exponent sign right after
+    // `e`/`E` (as in `1e-3`) is part of the literal too.
+    fn take_number_word(&mut self, lexeme: &mut String) {
+        while let Some(c) = self.source.peek_char() {
+            let exponent_sign = (c == '+' || c == '-')
+                && lexeme.ends_with(['e', 'E'])
+                && !Scanner::has_radix_prefix(lexeme);
+            if Scanner::is_alphanumeric(c) || exponent_sign {
+                lexeme.push(c);
+                self.source.next_char();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn has_radix_prefix(lexeme: &str) -> bool {
+        lexeme.starts_with('0') && matches!(lexeme.chars().nth(1), Some('x' | 'X' | 'b' | 'B' | 'o' | 'O'))
+    }
+
+    // Check that underscores only appear between two digits
+    fn check_underscores(lexeme: &str, digits: &str) -> Result<(), String> {
+        let chars: Vec<char> = digits.chars().collect();
+        for (i, c) in chars.iter().enumerate() {
+            if *c == '_' {
+                let before = i > 0 && chars[i - 1].is_ascii_alphanumeric();
+                let after = i + 1 < chars.len() && chars[i + 1].is_ascii_alphanumeric();
+                if !before || !after {
+                    return Err(format!("Misplaced '_' in number literal '{}'; it must be between digits.", lexeme));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn radix_literal(lexeme: &str) -> Result<String, String> {
+        let (radix, name) = match &lexeme[1..2] {
+            "x" | "X" => (16, "hex"),
+            "b" | "B" => (2, "binary"),
+            _ => (8, "octal"),
+        };
+        let digits = &lexeme[2..];
+        if digits.is_empty() {
+            return Err(format!("Expect {} digits after '{}' in number literal.", name, &lexeme[..2]));
+        }
+        Scanner::check_underscores(lexeme, digits)?;
+        let cleaned: String = digits.chars().filter(|c| *c != '_').collect();
+        if let Some(bad) = cleaned.chars().find(|c| !c.is_digit(radix)) {
+            return Err(format!("Invalid digit '{}' in {} literal '{}'.", bad, name, lexeme));
+        }
+        match u64::from_str_radix(&cleaned, radix) {
+            Ok(value) => Ok(value.to_string()),
+            Err(_) => Err(format!("Number literal '{}' is too large.", lexeme)),
+        }
+    }
+
+    fn decimal_literal(lexeme: &str) -> Result<String, String> {
+        // Split into integer part, optional fraction and optional exponent
+        let (mantissa, exponent) = match lexeme.find(['e', 'E']) {
+            Some(i) => (&lexeme[..i], Some(&lexeme[i + 1..])),
+            None => (lexeme, None),
+        };
+        let (int_part, frac_part) = match mantissa.find('.') {
+            Some(i) => (&mantissa[..i], Some(&mantissa[i + 1..])),
+            None => (mantissa, None),
+        };
+
+        Scanner::check_underscores(lexeme, int_part)?;
+        if let Some(bad) = int_part.chars().find(|c| !c.is_ascii_digit() && *c != '_') {
+            return Err(format!("Unexpected character '{}' in number literal '{}'.", bad, lexeme));
+        }
+        if let Some(frac) = frac_part {
+            if !frac.starts_with(|c: char| c.is_ascii_digit()) {
+                return Err(format!("Expect digit after '.' in number literal '{}'.", lexeme));
+            }
+            Scanner::check_underscores(lexeme, frac)?;
+            if let Some(bad) = frac.chars().find(|c| !c.is_ascii_digit() && *c != '_') {
+                return Err(format!("Unexpected character '{}' in number literal '{}'.", bad, lexeme));
+            }
+        }
+        if let Some(exp) = exponent {
+            let digits = exp.strip_prefix(['+', '-']).unwrap_or(exp);
+            if !digits.starts_with(|c: char| c.is_ascii_digit()) {
+                return Err(format!("Expect digits in exponent of number literal '{}'.", lexeme));
+            }
+            Scanner::check_underscores(lexeme, digits)?;
+            if let Some(bad) = digits.chars().find(|c| !c.is_ascii_digit() && *c != '_') {
+                return Err(format!("Unexpected character '{}' in number literal '{}'.", bad, lexeme));
+            }
+        }
+
+        let cleaned: String = lexeme.chars().filter(|c| *c != '_').collect();
+        if frac_part.is_none() && exponent.is_none() {
+            // Integer literals keep their exact digits
+            let trimmed = cleaned.trim_start_matches('0');
+            return Ok(if trimmed.is_empty() { "0".to_string() } else { trimmed.to_string() });
+        }
+        // Debug formatting always keeps a '.' or exponent, so the literal
+        // still reads as a fractional number ("1.0", "1e300")
+        match cleaned.parse::<f64>() {
+            // Too large for a float, e.g. 2e400
+            Ok(value) if value.is_infinite() => Err("Number literal out of range.".to_string()),
+            Ok(value) => Ok(format!("{:?}", value)),
+            Err(_) => Err(format!("Invalid number literal '{}'.", lexeme)),
+        }
+    }
+
     // Take and return any recorded scanner errors. This clears the internal
     // error list so callers can inspect errors after tokenization.
     pub fn take_errors(&mut self) -> Vec<String> {
         std::mem::take(&mut self.errors)
     }
 
+    // Make `token` the next one returned, ahead of the rest of the input.
+    // Only valid straight after next_token(), before anything is peeked.
+    pub fn push_back(&mut self, token: Token) {
+        debug_assert!(!self.next_token_loaded, "push_back over a peeked token");
+        self.next_token_cache = token;
+        self.next_token_loaded = true;
+    }
+
     pub fn peek_token(&mut self) -> Option<Token> {
         if !self.next_token_loaded {
             self.load_token();
@@ -0,0 +1,295 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+// Arbitrary-precision integer used when i64 arithmetic overflows.
+// Sign-magnitude with little-endian base 2^32 limbs. The magnitude never has
+// trailing zero limbs, and zero is an empty magnitude that is never negative.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    mag: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn from_i64(n: i64) -> Self {
+        let mut value = n.unsigned_abs();
+        let mut mag = Vec::new();
+        while value > 0 {
+            mag.push(value as u32);
+            value >>= 32;
+        }
+        BigInt { negative: n < 0, mag }
+    }
+
+    // Parse optional '-' followed by decimal digits
+    pub fn parse(text: &str) -> Option<Self> {
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let mut mag: Vec<u32> = Vec::new();
+        for b in digits.bytes() {
+            mag_mul_small_add(&mut mag, 10, (b - b'0') as u32);
+        }
+        Some(BigInt::new(negative, mag))
+    }
+
+    fn new(negative: bool, mut mag: Vec<u32>) -> Self {
+        while mag.last() == Some(&0) {
+            mag.pop();
+        }
+        let negative = negative && !mag.is_empty();
+        BigInt { negative, mag }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mag.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    // The value as an i64 if it fits
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.mag.len() > 2 {
+            return None;
+        }
+        let mut value: u64 = 0;
+        for (i, limb) in self.mag.iter().enumerate() {
+            value |= (*limb as u64) << (32 * i);
+        }
+        if self.negative {
+            if value <= i64::MAX as u64 + 1 {
+                return Some((value as i64).wrapping_neg());
+            }
+            None
+        } else {
+            i64::try_from(value).ok()
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let mut value = 0.0;
+        for limb in self.mag.iter().rev() {
+            value = value * 4294967296.0 + *limb as f64;
+        }
+        if self.negative { -value } else { value }
+    }
+
+    // Number of bits in the magnitude
+    pub fn bits(&self) -> u64 {
+        match self.mag.last() {
+            Some(top) => (self.mag.len() as u64 - 1) * 32 + (32 - top.leading_zeros() as u64),
+            None => 0,
+        }
+    }
+
+    pub fn neg(&self) -> Self {
+        BigInt::new(!self.negative, self.mag.clone())
+    }
+
+    pub fn add(&self, other: &BigInt) -> Self {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, mag_add(&self.mag, &other.mag));
+        }
+        // Opposite signs: subtract the smaller magnitude from the larger
+        match mag_cmp(&self.mag, &other.mag) {
+            Ordering::Less => BigInt::new(other.negative, mag_sub(&other.mag, &self.mag)),
+            _ => BigInt::new(self.negative, mag_sub(&self.mag, &other.mag)),
+        }
+    }
+
+    pub fn sub(&self, other: &BigInt) -> Self {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &BigInt) -> Self {
+        BigInt::new(self.negative != other.negative, mag_mul(&self.mag, &other.mag))
+    }
+
+    // Truncated division: the remainder has the sign of the dividend.
+    // None when dividing by zero.
+    pub fn div_rem(&self, other: &BigInt) -> Option<(BigInt, BigInt)> {
+        if other.is_zero() {
+            return None;
+        }
+        let (q, r) = mag_div_rem(&self.mag, &other.mag);
+        Some((BigInt::new(self.negative != other.negative, q), BigInt::new(self.negative, r)))
+    }
+
+    // Division rounding toward negative infinity
+    pub fn div_floor(&self, other: &BigInt) -> Option<BigInt> {
+        let (q, r) = self.div_rem(other)?;
+        if !r.is_zero() && self.negative != other.negative {
+            return Some(q.sub(&BigInt::from_i64(1)));
+        }
+        Some(q)
+    }
+
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = BigInt::from_i64(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(&base);
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.mul(&base);
+            }
+        }
+        result
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => mag_cmp(&self.mag, &other.mag),
+            (true, true) => mag_cmp(&other.mag, &self.mag),
+        }
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        // Peel off base 10^9 chunks, least significant first
+        let mut chunks = Vec::new();
+        let mut mag = self.mag.clone();
+        while !mag.is_empty() {
+            chunks.push(mag_div_small(&mut mag, 1_000_000_000));
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", chunks.last().unwrap())?;
+        for chunk in chunks.iter().rev().skip(1) {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+fn mag_cmp(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn mag_add(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+    for i in 0..a.len().max(b.len()) {
+        let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        out.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        out.push(carry as u32);
+    }
+    out
+}
+
+// a - b where a >= b
+fn mag_sub(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &digit) in a.iter().enumerate() {
+        let mut diff = digit as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += 1 << 32;
+            borrow = 1;
+        }
+        out.push(diff as u32);
+    }
+    while out.last() == Some(&0) {
+        out.pop();
+    }
+    out
+}
+
+fn mag_mul(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut out = vec![0u32; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, y) in b.iter().enumerate() {
+            let cur = out[i + j] as u64 + *x as u64 * *y as u64 + carry;
+            out[i + j] = cur as u32;
+            carry = cur >> 32;
+        }
+        out[i + b.len()] = carry as u32;
+    }
+    while out.last() == Some(&0) {
+        out.pop();
+    }
+    out
+}
+
+fn mag_mul_small_add(mag: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = addend as u64;
+    for limb in mag.iter_mut() {
+        let cur = *limb as u64 * factor as u64 + carry;
+        *limb = cur as u32;
+        carry = cur >> 32;
+    }
+    if carry > 0 {
+        mag.push(carry as u32);
+    }
+}
+
+// Divide in place by a small divisor and return the remainder
+fn mag_div_small(mag: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut rem = 0u64;
+    for limb in mag.iter_mut().rev() {
+        let cur = (rem << 32) | *limb as u64;
+        *limb = (cur / divisor as u64) as u32;
+        rem = cur % divisor as u64;
+    }
+    while mag.last() == Some(&0) {
+        mag.pop();
+    }
+    rem as u32
+}
+
+// Schoolbook binary long division; fine for the sizes scripts produce
+fn mag_div_rem(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if mag_cmp(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+    if b.len() == 1 {
+        let mut q = a.to_vec();
+        let r = mag_div_small(&mut q, b[0]);
+        return (q, if r == 0 { Vec::new() } else { vec![r] });
+    }
+    let mut q = vec![0u32; a.len()];
+    let mut r: Vec<u32> = Vec::new();
+    for i in (0..a.len() * 32).rev() {
+        // r = r * 2 + bit i of a
+        mag_mul_small_add(&mut r, 2, (a[i / 32] >> (i % 32)) & 1);
+        if mag_cmp(&r, b) != Ordering::Less {
+            r = mag_sub(&r, b);
+            q[i / 32] |= 1 << (i % 32);
+        }
+    }
+    while q.last() == Some(&0) {
+        q.pop();
+    }
+    (q, r)
+}
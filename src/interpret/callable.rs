@@ -1,10 +1,54 @@
 use crate::interpret::value::Value;
-use crate::interpret::interpreter::{Interpreter, RuntimeError};
+use crate::interpret::bigint::BigInt;
+use crate::interpret::interpreter::{plural, Interpreter, RuntimeError};
+use crate::token::token::Token;
+use std::fmt;
+
+// How many arguments a callable accepts: between `min` and `max`, with no
+// upper limit when `max` is None (rest parameters).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Arity {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl Arity {
+    pub fn exact(n: usize) -> Self {
+        Arity { min: n, max: Some(n) }
+    }
+
+    pub fn range(min: usize, max: usize) -> Self {
+        Arity { min, max: Some(max) }
+    }
+
+    pub fn at_least(min: usize) -> Self {
+        Arity { min, max: None }
+    }
+
+    pub fn accepts(&self, count: usize) -> bool {
+        count >= self.min && self.max.is_none_or(|max| count <= max)
+    }
+}
+
+// Formatted for "Expected {} but got {}.", e.g. "1 argument",
+// "1 to 2 arguments" or "at least 1 argument"
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "{}", plural(max, "argument")),
+            Some(max) => write!(f, "{} to {}", self.min, plural(max, "argument")),
+            None => write!(f, "at least {}", plural(self.min, "argument")),
+        }
+    }
+}
 
 // Trait representing any callable Lox value (native or user-defined).
+// `paren` is the closing parenthesis of the call expression so natives can
+// report errors at the call site.
 pub trait LoxCallable {
-    fn arity(&self) -> usize;
-    fn call(&self, interpreter: &mut Interpreter, arguments: &Vec<Value>) -> Result<Option<Value>, RuntimeError>;
+    // Range of accepted argument counts, checked before call().
+    fn arity(&self) -> Arity;
+    fn call(&self, interpreter: &mut Interpreter, paren: &Token, arguments: &Vec<Value>) -> Result<Option<Value>, RuntimeError>;
     fn to_string(&self) -> String;
 }
 
@@ -12,9 +56,9 @@ pub trait LoxCallable {
 pub struct NativeClock;
 
 impl LoxCallable for NativeClock {
-    fn arity(&self) -> usize { 0 }
+    fn arity(&self) -> Arity { Arity::exact(0) }
 
-    fn call(&self, _interpreter: &mut Interpreter, _arguments: &Vec<Value>) -> Result<Option<Value>, RuntimeError> {
+    fn call(&self, _interpreter: &mut Interpreter, _paren: &Token, _arguments: &Vec<Value>) -> Result<Option<Value>, RuntimeError> {
         let secs = (std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
@@ -24,3 +68,97 @@ impl LoxCallable for NativeClock {
 
     fn to_string(&self) -> String { "<native fn>".to_string() }
 }
+
+// Signature shared by natives built with NativeFunction.
+pub type NativeFn = fn(&mut Interpreter, &Token, &[Value]) -> Result<Option<Value>, RuntimeError>;
+
+// A native function backed by a plain Rust function pointer. Standard
+// library modules use this instead of one struct per native.
+pub struct NativeFunction {
+    pub name: String,
+    arity: Arity,
+    function: NativeFn,
+}
+
+impl NativeFunction {
+    pub fn new(name: &str, arity: usize, function: NativeFn) -> Self {
+        NativeFunction { name: name.to_string(), arity: Arity::exact(arity), function }
+    }
+
+    // A native whose last `arity - min_arity` arguments are optional.
+    pub fn new_optional(name: &str, min_arity: usize, arity: usize, function: NativeFn) -> Self {
+        NativeFunction { name: name.to_string(), arity: Arity::range(min_arity, arity), function }
+    }
+}
+
+impl LoxCallable for NativeFunction {
+    fn arity(&self) -> Arity { self.arity }
+
+    fn call(&self, interpreter: &mut Interpreter, paren: &Token, arguments: &Vec<Value>) -> Result<Option<Value>, RuntimeError> {
+        (self.function)(interpreter, paren, arguments)
+    }
+
+    fn to_string(&self) -> String { "<native fn>".to_string() }
+}
+
+// type(x): name of the runtime type of a value.
+pub struct NativeType;
+
+impl LoxCallable for NativeType {
+    fn arity(&self) -> Arity { Arity::exact(1) }
+
+    fn call(&self, _interpreter: &mut Interpreter, _paren: &Token, arguments: &Vec<Value>) -> Result<Option<Value>, RuntimeError> {
+        Ok(Some(Value::Str(arguments[0].type_name().to_string())))
+    }
+
+    fn to_string(&self) -> String { "<native fn>".to_string() }
+}
+
+// str(x): convert any value to the string `print` would show for it.
+pub struct NativeStr;
+
+impl LoxCallable for NativeStr {
+    fn arity(&self) -> Arity { Arity::exact(1) }
+
+    fn call(&self, interpreter: &mut Interpreter, _paren: &Token, arguments: &Vec<Value>) -> Result<Option<Value>, RuntimeError> {
+        Ok(Some(Value::Str(interpreter.stringify(&Some(arguments[0].clone())))))
+    }
+
+    fn to_string(&self) -> String { "<native fn>".to_string() }
+}
+
+// num(s): parse a string into a number. Returns nil if the string is not a
+// valid number; numbers are returned unchanged.
+pub struct NativeNum;
+
+impl LoxCallable for NativeNum {
+    fn arity(&self) -> Arity { Arity::exact(1) }
+
+    fn call(&self, _interpreter: &mut Interpreter, paren: &Token, arguments: &Vec<Value>) -> Result<Option<Value>, RuntimeError> {
+        match &arguments[0] {
+            Value::Number(_) | Value::Int(_) | Value::BigInt(_) => Ok(Some(arguments[0].clone())),
+            // Whole-number text becomes an integer, anything else a float
+            Value::Str(s) => match (BigInt::parse(s.trim()), s.trim().parse::<f64>()) {
+                (Some(n), _) => Ok(Some(Value::from_bigint(n))),
+                (_, Ok(n)) => Ok(Some(Value::Number(n))),
+                _ => Ok(Some(Value::Nil)),
+            },
+            _ => Err(RuntimeError::new(paren.clone(), "num() argument must be a string or number.")),
+        }
+    }
+
+    fn to_string(&self) -> String { "<native fn>".to_string() }
+}
+
+// bool(x): truthiness of a value using the same rules as `if`.
+pub struct NativeBool;
+
+impl LoxCallable for NativeBool {
+    fn arity(&self) -> Arity { Arity::exact(1) }
+
+    fn call(&self, _interpreter: &mut Interpreter, _paren: &Token, arguments: &Vec<Value>) -> Result<Option<Value>, RuntimeError> {
+        Ok(Some(Value::Bool(Interpreter::is_truthy(&Some(arguments[0].clone())))))
+    }
+
+    fn to_string(&self) -> String { "<native fn>".to_string() }
+}
@@ -0,0 +1,137 @@
+use std::cmp::Ordering;
+use std::rc::Rc;
+use crate::interpret::bigint::BigInt;
+use crate::interpret::value::Value;
+
+// Total ordering over all values, used by `<`/`>`, `==`, compare() and sort().
+//
+// Values of different kinds order by kind:
+//   nil < bool < number < string < list < map < error < function < native < namespace < iterable
+// Within a kind:
+// - bools: false < true
+// - numbers compare by value across ints, big ints and floats (1 == 1.0);
+//   NaN is equal to itself and greater than every other number
+// - strings compare lexicographically by character
+// - lists compare element by element, then by length
+// - maps compare as their sorted (key, value) pairs
+// - errors, functions, natives, namespaces and iterables are only equal to themselves
+//   and otherwise ordered consistently but arbitrarily (by identity)
+// Lists and maps that contain themselves compare as equal once the same
+// pair of containers comes up again.
+pub fn compare_values(a: &Value, b: &Value) -> Ordering {
+    compare_nested(a, b, &mut Vec::new())
+}
+
+// `open` holds the (a, b) pairs of lists and maps being compared further up
+fn compare_nested(a: &Value, b: &Value, open: &mut Vec<(*const (), *const ())>) -> Ordering {
+    let (rank_a, rank_b) = (kind_rank(a), kind_rank(b));
+    if rank_a != rank_b {
+        return rank_a.cmp(&rank_b);
+    }
+    match (a, b) {
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        (Value::Str(x), Value::Str(y)) => x.cmp(y),
+        (Value::List(x), Value::List(y)) => {
+            let pair = (Rc::as_ptr(x) as *const (), Rc::as_ptr(y) as *const ());
+            if Rc::ptr_eq(x, y) || open.contains(&pair) {
+                return Ordering::Equal;
+            }
+            open.push(pair);
+            let (x, y) = (x.borrow(), y.borrow());
+            let ordering = x.iter().zip(y.iter())
+                .map(|(item_x, item_y)| compare_nested(item_x, item_y, open))
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or_else(|| x.len().cmp(&y.len()));
+            open.pop();
+            ordering
+        }
+        (Value::Map(x), Value::Map(y)) => {
+            let pair = (Rc::as_ptr(x) as *const (), Rc::as_ptr(y) as *const ());
+            if Rc::ptr_eq(x, y) || open.contains(&pair) {
+                return Ordering::Equal;
+            }
+            open.push(pair);
+            let (x, y) = (x.borrow(), y.borrow());
+            let ordering = x.iter().zip(y.iter())
+                .map(|((key_x, item_x), (key_y, item_y))| key_x.cmp(key_y).then_with(|| compare_nested(item_x, item_y, open)))
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or_else(|| x.len().cmp(&y.len()));
+            open.pop();
+            ordering
+        }
+        (Value::Error(x), Value::Error(y)) => Rc::as_ptr(x).cmp(&Rc::as_ptr(y)),
+        (Value::Function(x), Value::Function(y)) => Rc::as_ptr(x).cmp(&Rc::as_ptr(y)),
+        (Value::Native(x), Value::Native(y)) => (Rc::as_ptr(x) as *const ()).cmp(&(Rc::as_ptr(y) as *const ())),
+        (Value::Namespace(x), Value::Namespace(y)) => Rc::as_ptr(x).cmp(&Rc::as_ptr(y)),
+        (Value::Iterable(x), Value::Iterable(y)) => (Rc::as_ptr(x) as *const ()).cmp(&(Rc::as_ptr(y) as *const ())),
+        (Value::Generator(x), Value::Generator(y)) => Rc::as_ptr(x).cmp(&Rc::as_ptr(y)),
+        (Value::Nil, Value::Nil) => Ordering::Equal,
+        _ => compare_numbers(a, b),
+    }
+}
+
+fn kind_rank(value: &Value) -> u8 {
+    match value {
+        Value::Nil => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) | Value::Int(_) | Value::BigInt(_) => 2,
+        Value::Str(_) => 3,
+        Value::List(_) => 4,
+        Value::Map(_) => 5,
+        Value::Error(_) => 6,
+        Value::Function(_) => 7,
+        Value::Native(_) => 8,
+        Value::Namespace(_) => 9,
+        Value::Iterable(_) => 10,
+        Value::Generator(_) => 11,
+    }
+}
+
+// Exact comparison of two numeric values of any representation
+fn compare_numbers(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Int(x), Value::Int(y)) => x.cmp(y),
+        (Value::Number(x), Value::Number(y)) => compare_floats(*x, *y),
+        (Value::Number(x), other) => compare_float_to_int(*x, other),
+        (other, Value::Number(y)) => compare_float_to_int(*y, other).reverse(),
+        _ => as_bigint(a).cmp(&as_bigint(b)),
+    }
+}
+
+fn compare_floats(x: f64, y: f64) -> Ordering {
+    match (x.is_nan(), y.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        // -0.0 and 0.0 compare equal
+        (false, false) => x.partial_cmp(&y).unwrap(),
+    }
+}
+
+// Compare float `x` with an integer value without losing precision
+fn compare_float_to_int(x: f64, int: &Value) -> Ordering {
+    if x.is_nan() || x == f64::INFINITY {
+        return Ordering::Greater;
+    }
+    if x == f64::NEG_INFINITY {
+        return Ordering::Less;
+    }
+    // Integers up to 2^53 convert to f64 exactly
+    if let Value::Int(n) = int && n.unsigned_abs() <= 1 << 53 {
+        return compare_floats(x, *n as f64);
+    }
+    // Otherwise compare the integer part exactly, then the fraction
+    let whole = BigInt::parse(&format!("{:.0}", x.trunc())).unwrap_or_else(|| BigInt::from_i64(0));
+    match whole.cmp(&as_bigint(int)) {
+        Ordering::Equal => compare_floats(x, x.trunc()),
+        ordering => ordering,
+    }
+}
+
+fn as_bigint(value: &Value) -> BigInt {
+    match value {
+        Value::Int(n) => BigInt::from_i64(*n),
+        Value::BigInt(n) => (**n).clone(),
+        _ => unreachable!("only integers reach as_bigint"),
+    }
+}
@@ -4,9 +4,17 @@ use std::cell::RefCell;
 use crate::interpret::value::Value;
 use crate::token::token::Token;
 
+// A variable's value (None until initialized) and whether it may be
+// reassigned. `const` declarations create immutable bindings.
+#[derive(Debug)]
+struct Binding {
+    value: Option<Value>,
+    mutable: bool,
+}
+
 #[derive(Debug)]
 pub struct Environment {
-    values: HashMap<String, Option<Value>>,
+    values: HashMap<String, Binding>,
     enclosing: Option<Rc<RefCell<Environment>>>,
 }
 
@@ -24,14 +32,25 @@ impl Environment {
     // Define or redefine a variable in the current environment. This always
     // affects only the current (innermost) scope.
     pub fn define(&mut self, name: &str, value: Option<Value>) {
-        self.values.insert(name.to_string(), value);
+        self.values.insert(name.to_string(), Binding { value, mutable: true });
+    }
+
+    // Define a constant in the current environment. Later assignments to it
+    // fail, though it can still be shadowed or redefined by a declaration.
+    pub fn define_const(&mut self, name: &str, value: Option<Value>) {
+        self.values.insert(name.to_string(), Binding { value, mutable: false });
+    }
+
+    // Names and values defined directly in this scope (not enclosing ones)
+    pub fn bindings(&self) -> Vec<(String, Option<Value>)> {
+        self.values.iter().map(|(name, binding)| (name.clone(), binding.value.clone())).collect()
     }
 
     // Get a variable's value by token. Walks the chain of enclosing
     // environments outward until the variable is found or we reach the root.
     pub fn get(&self, name: &Token) -> Result<Option<Value>, String> {
-        if let Some(val) = self.values.get(&name.lexeme) {
-            return Ok(val.clone());
+        if let Some(binding) = self.values.get(&name.lexeme) {
+            return Ok(binding.value.clone());
         }
 
         if let Some(enclos) = &self.enclosing {
@@ -43,10 +62,29 @@ impl Environment {
 
     // Assign to an existing variable, walking enclosing environments if
     // necessary. Returns Err if the variable doesn't exist in any enclosing
-    // scope.
+    // scope, or if it is a constant.
+    // The error assign() would report for `name`, without assigning
+    pub fn check_assignable(&self, name: &Token) -> Result<(), String> {
+        if let Some(binding) = self.values.get(&name.lexeme) {
+            if !binding.mutable {
+                return Err(format!("Cannot assign to constant '{}'.", name.lexeme));
+            }
+            return Ok(());
+        }
+
+        if let Some(enclos) = &self.enclosing {
+            return enclos.borrow().check_assignable(name);
+        }
+
+        Err(format!("Undefined variable '{}'.", name.lexeme))
+    }
+
     pub fn assign(&mut self, name: &Token, value: Option<Value>) -> Result<(), String> {
-        if self.values.contains_key(&name.lexeme) {
-            self.values.insert(name.lexeme.clone(), value);
+        if let Some(binding) = self.values.get_mut(&name.lexeme) {
+            if !binding.mutable {
+                return Err(format!("Cannot assign to constant '{}'.", name.lexeme));
+            }
+            binding.value = value;
             return Ok(());
         }
 
@@ -57,3 +95,9 @@ impl Environment {
         Err(format!("Undefined variable '{}'.", name.lexeme))
     }
 }
+
+impl Default for Environment {
+    fn default() -> Self {
+        Environment::new()
+    }
+}
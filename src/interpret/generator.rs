@@ -0,0 +1,461 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use crate::interpret::callable::{Arity, LoxCallable};
+use crate::interpret::environment::Environment;
+use crate::interpret::interpreter::{Interpreter, RuntimeError};
+use crate::interpret::iterator::LoxIterator;
+use crate::interpret::return_value::QuietPanics;
+use crate::interpret::value::{ErrorValue, Value};
+use crate::parse::expr::Expr;
+use crate::parse::stmt::{MatchArm, Stmt};
+use crate::token::token::Token;
+
+// Generators run their body one step at a time so it can stop at a `yield`
+// and carry on from there on the next call to next(). Ordinary functions run
+// on the Rust stack, which can't be suspended, so a generator keeps its own
+// stack of frames instead.
+//
+// The body is compiled into Nodes once, when the `fun*` is declared.
+// Statements without a yield stay whole and run on the normal interpreter;
+// only the statements that contain a yield are broken into frames.
+
+// Generator body statement. Compound statements hold their children behind
+// Rc so a suspended frame can keep pointing at them.
+enum Node {
+    // No yield inside: executed in one go by the interpreter
+    Plain(Stmt),
+    Yield { value: Option<Expr> },
+    Block(Vec<Rc<Node>>),
+    If { condition: Expr, then_branch: Rc<Node>, else_branch: Option<Rc<Node>> },
+    While { condition: Expr, body: Rc<Node> },
+    ForIn { name: Token, iterable: Expr, body: Rc<Node> },
+    // `arms` still hold their statements for matching; `bodies` are the
+    // compiled arm bodies in the same order
+    Match { subject: Expr, arms: Vec<MatchArm>, bodies: Vec<Rc<Node>> },
+    Try { body: Rc<Node>, catch_name: Option<Token>, catch_body: Option<Rc<Node>>, finally_body: Option<Rc<Node>> },
+}
+
+fn contains_yield(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Yield { .. } => true,
+        Stmt::Block(stmts) => stmts.iter().any(contains_yield),
+        Stmt::If { then_branch, else_branch, .. } => contains_yield(then_branch) || else_branch.as_deref().is_some_and(contains_yield),
+        Stmt::While { body, .. } | Stmt::ForIn { body, .. } => contains_yield(body),
+        Stmt::Match { arms, .. } => arms.iter().any(|arm| contains_yield(&arm.body)),
+        Stmt::Try { body, catch_body, finally_body, .. } => {
+            body.iter().any(contains_yield)
+                || catch_body.iter().flatten().any(contains_yield)
+                || finally_body.iter().flatten().any(contains_yield)
+        }
+        // A nested function's yields (if it is a generator) belong to it
+        _ => false,
+    }
+}
+
+fn compile(stmt: &Stmt) -> Node {
+    if !contains_yield(stmt) {
+        return Node::Plain(stmt.clone());
+    }
+    match stmt {
+        Stmt::Yield { value, .. } => Node::Yield { value: value.clone() },
+        Stmt::Block(stmts) => compile_block(stmts),
+        Stmt::If { condition, then_branch, else_branch } => Node::If {
+            condition: condition.clone(),
+            then_branch: Rc::new(compile(then_branch)),
+            else_branch: else_branch.as_ref().map(|s| Rc::new(compile(s))),
+        },
+        Stmt::While { condition, body } => Node::While { condition: condition.clone(), body: Rc::new(compile(body)) },
+        Stmt::ForIn { name, iterable, body } => Node::ForIn { name: name.clone(), iterable: iterable.clone(), body: Rc::new(compile(body)) },
+        Stmt::Match { subject, arms, .. } => Node::Match {
+            subject: subject.clone(),
+            arms: arms.clone(),
+            bodies: arms.iter().map(|arm| Rc::new(compile(&arm.body))).collect(),
+        },
+        Stmt::Try { body, catch_name, catch_body, finally_body } => Node::Try {
+            body: Rc::new(compile_block(body)),
+            catch_name: catch_name.clone(),
+            catch_body: catch_body.as_ref().map(|s| Rc::new(compile_block(s))),
+            finally_body: finally_body.as_ref().map(|s| Rc::new(compile_block(s))),
+        },
+        other => Node::Plain(other.clone()),
+    }
+}
+
+fn compile_block(stmts: &[Stmt]) -> Node {
+    Node::Block(stmts.iter().map(|s| Rc::new(compile(s))).collect())
+}
+
+// A `fun*` body compiled once when the function is declared and shared by
+// every generator its calls create
+#[derive(Clone)]
+pub struct GeneratorBody(Rc<Node>);
+
+impl GeneratorBody {
+    pub fn compile(body: &[Stmt]) -> Self {
+        GeneratorBody(Rc::new(compile_block(body)))
+    }
+}
+
+impl std::fmt::Debug for GeneratorBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<generator body>")
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum TryStage {
+    Body,
+    Catch,
+    Finally,
+}
+
+// A statement the generator is part way through
+enum Frame {
+    Block { node: Rc<Node>, index: usize, env: Rc<RefCell<Environment>> },
+    While { node: Rc<Node>, env: Rc<RefCell<Environment>> },
+    ForIn { node: Rc<Node>, iterator: Box<dyn LoxIterator>, env: Rc<RefCell<Environment>> },
+    // `pending` is the return or error to resume once finally is done
+    Try { node: Rc<Node>, stage: TryStage, pending: Option<Signal>, env: Rc<RefCell<Environment>> },
+}
+
+// What running one step produced
+enum Signal {
+    Continue,
+    Yield(Value),
+    Return(Option<Value>),
+    Error(RuntimeError),
+}
+
+struct GeneratorState {
+    frames: Vec<Frame>,
+    done: bool,
+    running: bool,
+}
+
+// The object returned by calling a `fun*` function
+pub struct Generator {
+    pub name: String,
+    state: RefCell<GeneratorState>,
+}
+
+// Result of resuming a generator
+pub enum Resumed {
+    Yielded(Value),
+    // Finished, with the value of its `return` (nil without one). Later
+    // resumes keep reporting Done(nil).
+    Done(Value),
+}
+
+impl Generator {
+    // A generator that hasn't started yet; `env` holds the bound parameters.
+    pub fn new(name: &str, body: &GeneratorBody, env: Rc<RefCell<Environment>>) -> Self {
+        let frame = Frame::Block { node: body.0.clone(), index: 0, env };
+        Generator {
+            name: name.to_string(),
+            state: RefCell::new(GeneratorState { frames: vec![frame], done: false, running: false }),
+        }
+    }
+
+    // Run until the next yield or the end of the body. `token` locates
+    // errors about the generator itself (e.g. resuming it from inside).
+    pub fn resume(&self, interpreter: &mut Interpreter, token: &Token) -> Result<Resumed, RuntimeError> {
+        let mut frames = {
+            let mut state = self.state.borrow_mut();
+            if state.running {
+                return Err(RuntimeError::new(token.clone(), &format!("Generator '{}' is already running.", self.name)));
+            }
+            if state.done {
+                return Ok(Resumed::Done(Value::Nil));
+            }
+            state.running = true;
+            std::mem::take(&mut state.frames)
+        };
+
+        // `return` inside plain statements unwinds as a panic; keep the
+        // default hook from printing it while the body runs
+        let guard = ResumeGuard { state: &self.state, _quiet: QuietPanics::install() };
+        let outcome = run(&mut frames, interpreter);
+        drop(guard);
+
+        let mut state = self.state.borrow_mut();
+        let result = match outcome {
+            Signal::Yield(value) => Ok(Resumed::Yielded(value)),
+            Signal::Return(value) => Ok(Resumed::Done(value.unwrap_or(Value::Nil))),
+            Signal::Error(error) => Err(error),
+            Signal::Continue => Ok(Resumed::Done(Value::Nil)),
+        };
+        if matches!(result, Ok(Resumed::Yielded(_))) {
+            state.frames = frames;
+        } else {
+            state.done = true;
+        }
+        result
+    }
+}
+
+// Undoes resume()'s setup when the body stops, even when a panic other than
+// a Lox return unwinds out of it: puts the previous panic hook back and
+// marks the generator as not running.
+struct ResumeGuard<'a> {
+    state: &'a RefCell<GeneratorState>,
+    _quiet: QuietPanics,
+}
+
+impl Drop for ResumeGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.try_borrow_mut() {
+            state.running = false;
+        }
+    }
+}
+
+// Step the frames until something other than Continue comes out of the
+// outermost frame. Returns Yield, or Return/Error/Continue once finished.
+fn run(frames: &mut Vec<Frame>, interpreter: &mut Interpreter) -> Signal {
+    loop {
+        let mut signal = step(frames, interpreter);
+        // Returns and errors unwind to the nearest try that deals with them
+        while matches!(signal, Signal::Return(_) | Signal::Error(_)) {
+            if frames.is_empty() {
+                return signal;
+            }
+            signal = unwind(frames, interpreter, signal);
+        }
+        if let Signal::Yield(_) = signal {
+            return signal;
+        }
+        if frames.is_empty() {
+            return Signal::Continue;
+        }
+    }
+}
+
+fn step(frames: &mut Vec<Frame>, interpreter: &mut Interpreter) -> Signal {
+    let top = match frames.last_mut() {
+        Some(frame) => frame,
+        None => return Signal::Continue,
+    };
+    match top {
+        Frame::Block { node, index, env } => {
+            let child = match &**node {
+                Node::Block(children) => children.get(*index).cloned(),
+                _ => None,
+            };
+            *index += 1;
+            let env = env.clone();
+            match child {
+                Some(child) => enter(frames, interpreter, child, env),
+                None => {
+                    frames.pop();
+                    Signal::Continue
+                }
+            }
+        }
+        Frame::While { node, env } => {
+            let (node, env) = (node.clone(), env.clone());
+            if let Node::While { condition, body } = &*node {
+                match interpreter.evaluate_in(condition, env.clone()) {
+                    Ok(value) if Interpreter::is_truthy(&value) => return enter(frames, interpreter, body.clone(), env),
+                    Ok(_) => {}
+                    Err(error) => return Signal::Error(error),
+                }
+            }
+            frames.pop();
+            Signal::Continue
+        }
+        Frame::ForIn { node, iterator, env } => {
+            let (node, env) = (node.clone(), env.clone());
+            let item = match iterator.next(interpreter) {
+                Ok(item) => item,
+                Err(error) => return Signal::Error(error),
+            };
+            match (item, &*node) {
+                (Some(item), Node::ForIn { name, body, .. }) => {
+                    // Fresh binding per iteration, as in the interpreter
+                    let iteration = Rc::new(RefCell::new(Environment::new_enclosing(env)));
+                    iteration.borrow_mut().define(&name.lexeme, Some(item));
+                    enter(frames, interpreter, body.clone(), iteration)
+                }
+                _ => {
+                    frames.pop();
+                    Signal::Continue
+                }
+            }
+        }
+        // The current part of the try finished normally
+        Frame::Try { node, stage, pending, env } => {
+            let (node, env) = (node.clone(), env.clone());
+            let finally_body = match &*node {
+                Node::Try { finally_body, .. } => finally_body.clone(),
+                _ => None,
+            };
+            match (*stage, finally_body) {
+                (TryStage::Body | TryStage::Catch, Some(cleanup)) => {
+                    *stage = TryStage::Finally;
+                    *pending = None;
+                    enter_scope(frames, interpreter, cleanup, env)
+                }
+                _ => {
+                    let pending = pending.take();
+                    frames.pop();
+                    pending.unwrap_or(Signal::Continue)
+                }
+            }
+        }
+    }
+}
+
+// Pop frames for a return or error until a try catches it or has a finally
+// to run first. Returns the signal to carry on with.
+fn unwind(frames: &mut Vec<Frame>, interpreter: &mut Interpreter, signal: Signal) -> Signal {
+    while let Some(frame) = frames.pop() {
+        let Frame::Try { node, stage, env, .. } = frame else { continue };
+        let Node::Try { catch_name, catch_body, finally_body, .. } = &*node else { continue };
+
+        if let (TryStage::Body, Signal::Error(error), Some(handler)) = (stage, &signal, catch_body) {
+            let caught = match &error.value {
+                Some(v) => v.clone(),
+                None => Value::Error(Rc::new(ErrorValue { message: error.message.clone(), line: error.token.line })),
+            };
+            let scope = Rc::new(RefCell::new(Environment::new_enclosing(env.clone())));
+            if let Some(name) = catch_name {
+                scope.borrow_mut().define(&name.lexeme, Some(caught));
+            }
+            let handler = handler.clone();
+            frames.push(Frame::Try { node: node.clone(), stage: TryStage::Catch, pending: None, env });
+            return enter(frames, interpreter, handler, scope);
+        }
+        if let (TryStage::Body | TryStage::Catch, Some(cleanup)) = (stage, finally_body) {
+            let cleanup = cleanup.clone();
+            frames.push(Frame::Try { node: node.clone(), stage: TryStage::Finally, pending: Some(signal), env: env.clone() });
+            return enter_scope(frames, interpreter, cleanup, env);
+        }
+        // A return or error out of finally replaces whatever was pending
+    }
+    signal
+}
+
+// Enter `node` in a new scope enclosing `env`
+fn enter_scope(frames: &mut Vec<Frame>, interpreter: &mut Interpreter, node: Rc<Node>, env: Rc<RefCell<Environment>>) -> Signal {
+    let scope = Rc::new(RefCell::new(Environment::new_enclosing(env)));
+    enter(frames, interpreter, node, scope)
+}
+
+// Start running `node` in `env`: plain statements and yields finish right
+// away, compound statements push a frame.
+fn enter(frames: &mut Vec<Frame>, interpreter: &mut Interpreter, node: Rc<Node>, env: Rc<RefCell<Environment>>) -> Signal {
+    match &*node {
+        Node::Plain(stmt) => run_plain(interpreter, stmt, env),
+        Node::Yield { value } => match value {
+            Some(expr) => match interpreter.evaluate_in(expr, env) {
+                Ok(value) => Signal::Yield(value.unwrap_or(Value::Nil)),
+                Err(error) => Signal::Error(error),
+            },
+            None => Signal::Yield(Value::Nil),
+        },
+        Node::Block(_) => {
+            let scope = Rc::new(RefCell::new(Environment::new_enclosing(env)));
+            frames.push(Frame::Block { node: node.clone(), index: 0, env: scope });
+            Signal::Continue
+        }
+        Node::If { condition, then_branch, else_branch } => match interpreter.evaluate_in(condition, env.clone()) {
+            Ok(value) if Interpreter::is_truthy(&value) => enter(frames, interpreter, then_branch.clone(), env),
+            Ok(_) => match else_branch {
+                Some(branch) => enter(frames, interpreter, branch.clone(), env),
+                None => Signal::Continue,
+            },
+            Err(error) => Signal::Error(error),
+        },
+        Node::While { .. } => {
+            frames.push(Frame::While { node: node.clone(), env });
+            Signal::Continue
+        }
+        Node::ForIn { name, iterable, .. } => {
+            let value = match interpreter.evaluate_in(iterable, env.clone()) {
+                Ok(value) => value.unwrap_or(Value::Nil),
+                Err(error) => return Signal::Error(error),
+            };
+            match crate::interpret::iterator::iterate(&value, name) {
+                Ok(iterator) => {
+                    frames.push(Frame::ForIn { node: node.clone(), iterator, env });
+                    Signal::Continue
+                }
+                Err(error) => Signal::Error(error),
+            }
+        }
+        Node::Match { subject, arms, bodies } => {
+            let value = match interpreter.evaluate_in(subject, env.clone()) {
+                Ok(value) => value.unwrap_or(Value::Nil),
+                Err(error) => return Signal::Error(error),
+            };
+            match interpreter.select_arm(&value, arms, env) {
+                Ok(Some((index, scope))) => enter(frames, interpreter, bodies[index].clone(), scope),
+                Ok(None) => Signal::Continue,
+                Err(error) => Signal::Error(error),
+            }
+        }
+        Node::Try { body, .. } => {
+            let body = body.clone();
+            frames.push(Frame::Try { node: node.clone(), stage: TryStage::Body, pending: None, env: env.clone() });
+            enter(frames, interpreter, body, env)
+        }
+    }
+}
+
+// Run a statement without yields on the normal interpreter, turning a Lox
+// `return` (which unwinds as a panic) into a Return signal.
+fn run_plain(interpreter: &mut Interpreter, stmt: &Stmt, env: Rc<RefCell<Environment>>) -> Signal {
+    use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+    match catch_unwind(AssertUnwindSafe(|| interpreter.execute_block(std::slice::from_ref(stmt), env))) {
+        Ok(Ok(())) => Signal::Continue,
+        Ok(Err(error)) => Signal::Error(error),
+        Err(payload) => {
+            if let Some(s) = payload.downcast_ref::<&str>()
+                && *s == crate::interpret::return_value::RETURN_MARKER
+            {
+                return Signal::Return(crate::interpret::return_value::take_return());
+            }
+            resume_unwind(payload);
+        }
+    }
+}
+
+// `{done, value}` map returned by next()
+fn step_result(done: bool, value: Value) -> Value {
+    let mut entries = BTreeMap::new();
+    entries.insert("done".to_string(), Value::Bool(done));
+    entries.insert("value".to_string(), value);
+    Value::Map(Rc::new(RefCell::new(entries)))
+}
+
+// gen.next(): resume the generator and report {done, value}
+pub struct GeneratorNext(pub Rc<Generator>);
+
+impl LoxCallable for GeneratorNext {
+    fn arity(&self) -> Arity { Arity::exact(0) }
+
+    fn call(&self, interpreter: &mut Interpreter, paren: &Token, _arguments: &Vec<Value>) -> Result<Option<Value>, RuntimeError> {
+        match self.0.resume(interpreter, paren)? {
+            Resumed::Yielded(value) => Ok(Some(step_result(false, value))),
+            Resumed::Done(value) => Ok(Some(step_result(true, value))),
+        }
+    }
+
+    fn to_string(&self) -> String { "<native fn>".to_string() }
+}
+
+// for-in over a generator runs it to the end, ignoring its return value
+pub struct GeneratorIterator {
+    pub generator: Rc<Generator>,
+    pub token: Token,
+}
+
+impl LoxIterator for GeneratorIterator {
+    fn next(&mut self, interpreter: &mut Interpreter) -> Result<Option<Value>, RuntimeError> {
+        match self.generator.resume(interpreter, &self.token)? {
+            Resumed::Yielded(value) => Ok(Some(value)),
+            Resumed::Done(_) => Ok(None),
+        }
+    }
+}
@@ -1,55 +1,245 @@
-use crate::parse::expr::{Expr, Visitor, BinaryExpr, GroupingExpr, LiteralExpr, UnaryExpr, AssignExpr, LogicalExpr};
-use crate::parse::stmt::{Stmt, Visitor as StmtVisitor};
+use crate::parse::expr::{Expr, Visitor, BinaryExpr, GroupingExpr, LiteralExpr, UnaryExpr, AssignExpr, LogicalExpr, GetExpr, ListExpr, IndexExpr, SetIndexExpr, CompoundAssignExpr, IncrementExpr, DestructureAssignExpr};
+use crate::parse::stmt::{Stmt, Param, MatchArm, Visitor as StmtVisitor};
+use crate::parse::pattern::Pattern;
+use crate::parse::types::Type;
 use crate::token::token::{TokenType, Token};
 use crate::interpret::environment::Environment;
 use crate::util::logger::LogLevel;
 use std::rc::Rc;
 use std::cell::RefCell;
-use crate::interpret::value::Value;
+use crate::interpret::value::{ErrorValue, Value};
+use crate::interpret::bigint::BigInt;
+use crate::interpret::compare::compare_values;
+use std::cmp::Ordering;
+use crate::interpret::generator::GeneratorNext;
+use crate::interpret::module::ModuleCache;
 
 // The Interpreter evaluates expressions and returns runtime values.
 // It keeps a simple global environment (flat scope) for variable declarations.
 pub struct Interpreter {
 	environment: Rc<RefCell<Environment>>,
+	// Where input()/read_line() read from. None means the process stdin,
+	// which shares its buffer with the REPL Reader.
+	input: Option<Box<dyn std::io::BufRead>>,
+	// File system natives (read_file, write_file, ...) only work when this
+	// is turned on, so the default REPL has no side effects on disk.
+	allow_file_system: bool,
+	// Strict mode makes reading a declared but never assigned variable a
+	// runtime error instead of yielding nil.
+	strict: bool,
+	// Generator behind random(), random_int() and shuffle(); seed() resets it.
+	rng: crate::interpret::stdlib::random::Rng,
+	// Root environment holding the natives; modules run in a scope inside it
+	globals: Rc<RefCell<Environment>>,
+	// File whose code is running, for resolving relative imports. None in
+	// the REPL, where imports resolve against the working directory.
+	script_path: Option<std::path::PathBuf>,
+	modules: ModuleCache,
 }
 
+// Arm chosen by a match statement: its index and the scope of its bindings
+pub(crate) type SelectedArm = (usize, Rc<RefCell<Environment>>);
+
 #[derive(Debug, Clone)]
 pub struct RuntimeError {
 	pub token: Token,
 	pub message: String,
+	// The value passed to `throw`, if this error came from a throw statement.
+	// Errors raised by the interpreter itself leave this empty.
+	pub value: Option<Value>,
+	// Set once the message says which module the error came from, so the
+	// imports around that module don't add their own names too
+	pub in_module: bool,
 }
 
 impl RuntimeError {
 	pub fn new(token: Token, message: &str) -> Self {
-		RuntimeError { token, message: message.to_string() }
+		RuntimeError { token, message: message.to_string(), value: None, in_module: false }
+	}
+
+	// An error carrying a script-thrown value.
+	pub fn thrown(token: Token, message: &str, value: Value) -> Self {
+		RuntimeError { token, message: message.to_string(), value: Some(value), in_module: false }
+	}
+}
+
+// An assignable location whose parts have already been evaluated, so
+// compound assignment and ++/-- read and write it without re-evaluating
+// the target expression.
+enum Place {
+	Variable(Token),
+	Index { object: Option<Value>, index: Option<Value>, bracket: Token },
+}
+
+// Operands of an arithmetic operator after promotion: both i64, both big
+// integers (when either side already was one), or both floats when either
+// side was a float.
+enum Numeric {
+	Ints(i64, i64),
+	Bigs(BigInt, BigInt),
+	Floats(f64, f64),
+}
+
+// Largest integer `**` result we are willing to build, in bits
+const MAX_POW_BITS: u64 = 1 << 20;
+
+impl Numeric {
+	fn as_floats(&self) -> (f64, f64) {
+		match self {
+			Numeric::Ints(a, b) => (*a as f64, *b as f64),
+			Numeric::Bigs(a, b) => (a.to_f64(), b.to_f64()),
+			Numeric::Floats(a, b) => (*a, *b),
+		}
+	}
+
+	fn as_bigs(&self) -> (BigInt, BigInt) {
+		match self {
+			Numeric::Ints(a, b) => (BigInt::from_i64(*a), BigInt::from_i64(*b)),
+			Numeric::Bigs(a, b) => (a.clone(), b.clone()),
+			Numeric::Floats(..) => unreachable!("float operands have no integer form"),
+		}
+	}
+
+	// Integer operands with a zero right-hand side
+	fn divides_by_zero(&self) -> bool {
+		match self {
+			Numeric::Ints(_, b) => *b == 0,
+			Numeric::Bigs(_, b) => b.is_zero(),
+			Numeric::Floats(..) => false,
+		}
+	}
+
+	// Apply an integer operation: i64 first, falling back to big integers
+	// when `small` overflows (returns None).
+	fn int_op(&self, small: fn(i64, i64) -> Option<i64>, big: fn(&BigInt, &BigInt) -> BigInt) -> Value {
+		if let Some(n) = match self { Numeric::Ints(a, b) => small(*a, *b), _ => None } {
+			return Value::Int(n);
+		}
+		let (a, b) = self.as_bigs();
+		Value::from_bigint(big(&a, &b))
 	}
 }
 
+// How a block that may `return` finished: normally (possibly with an error)
+// or by unwinding with a return value.
+enum Completion {
+	Normal(Result<(), RuntimeError>),
+	Return(Option<Value>),
+}
+
 impl Interpreter {
 	pub fn new() -> Self {
+		// Only the natives live here. The script's own top level is a scope
+		// inside it, like each module's.
 		let globals = Rc::new(RefCell::new(Environment::new()));
 		// Put native functions into globals
 		// Register clock native function
 		let clock = crate::interpret::callable::NativeClock;
 		globals.borrow_mut().define("clock", Some(crate::interpret::value::Value::Native(std::rc::Rc::new(clock))));
+		// Type introspection and conversions
+		globals.borrow_mut().define("type", Some(Value::Native(Rc::new(crate::interpret::callable::NativeType))));
+		globals.borrow_mut().define("str", Some(Value::Native(Rc::new(crate::interpret::callable::NativeStr))));
+		globals.borrow_mut().define("num", Some(Value::Native(Rc::new(crate::interpret::callable::NativeNum))));
+		globals.borrow_mut().define("bool", Some(Value::Native(Rc::new(crate::interpret::callable::NativeBool))));
+		// Standard library modules live under their own namespace value
+		globals.borrow_mut().define("math", Some(crate::interpret::stdlib::math::namespace()));
+		// String natives are plain globals
+		crate::interpret::stdlib::string::register(&mut globals.borrow_mut());
+		crate::interpret::stdlib::console::register(&mut globals.borrow_mut());
+		crate::interpret::stdlib::fs::register(&mut globals.borrow_mut());
+		crate::interpret::stdlib::random::register(&mut globals.borrow_mut());
+		crate::interpret::stdlib::json::register(&mut globals.borrow_mut());
+		crate::interpret::stdlib::assert::register(&mut globals.borrow_mut());
+		crate::interpret::stdlib::order::register(&mut globals.borrow_mut());
+		crate::interpret::stdlib::iter::register(&mut globals.borrow_mut());
+
+		Interpreter {
+			environment: Rc::new(RefCell::new(Environment::new_enclosing(globals.clone()))),
+			input: None,
+			allow_file_system: false,
+			strict: false,
+			rng: crate::interpret::stdlib::random::Rng::from_time(),
+			globals,
+			script_path: None,
+			modules: ModuleCache::new(),
+		}
+	}
+
+	pub(crate) fn rng(&mut self) -> &mut crate::interpret::stdlib::random::Rng {
+		&mut self.rng
+	}
+
+	// Opt in (or back out) of the file system natives.
+	pub fn set_allow_file_system(&mut self, allow: bool) {
+		self.allow_file_system = allow;
+	}
+
+	pub fn file_system_allowed(&self) -> bool {
+		self.allow_file_system
+	}
+
+	// The file being run, so `import` paths resolve relative to it.
+	pub fn set_script_path(&mut self, path: &str) {
+		self.script_path = Some(std::path::PathBuf::from(path));
+	}
+
+	// Turn strict mode (uninitialized reads are errors) on or off.
+	pub fn set_strict(&mut self, strict: bool) {
+		self.strict = strict;
+	}
 
-		Interpreter { environment: globals }
+	// Replace the source used by input()/read_line(), e.g. with an in-memory
+	// buffer in tests.
+	pub fn set_input(&mut self, input: Box<dyn std::io::BufRead>) {
+		self.input = Some(input);
+	}
+
+	// Read one line of console input without its line ending. Returns
+	// Ok(None) at EOF.
+	pub(crate) fn read_input_line(&mut self) -> std::io::Result<Option<String>> {
+		let mut line = String::new();
+		// io::stdin() is the same buffered handle the terminal Reader uses, so
+		// reading here only takes the next line and leaves the rest of the
+		// REPL's own buffer (the current line) untouched.
+		let n = match &mut self.input {
+			Some(reader) => reader.read_line(&mut line)?,
+			None => std::io::stdin().read_line(&mut line)?,
+		};
+		if n == 0 {
+			return Ok(None);
+		}
+		if line.ends_with('\n') {
+			line.pop();
+			if line.ends_with('\r') {
+				line.pop();
+			}
+		}
+		Ok(Some(line))
 	}
 
 	// Return the value of a global variable by name, if defined.
 	// This is primarily for tests to inspect the interpreter's global state.
 	pub fn get_global(&self, name: &str) -> Option<crate::interpret::value::Value> {
 		let token = Token::new_token(TokenType::Identifier, name.to_string(), None, 0);
-		match self.environment.borrow().get(&token) {
-			Ok(v) => v,
-			Err(_) => None,
-		}
+		self.environment.borrow().get(&token).unwrap_or_default()
+	}
+}
+
+impl Default for Interpreter {
+	fn default() -> Self {
+		Interpreter::new()
 	}
 }
 
 
 impl Interpreter {
-	fn stringify(&self, object: &Option<Value>) -> String {
+	pub fn stringify(&self, object: &Option<Value>) -> String {
+		self.stringify_nested(object, &mut Vec::new())
+	}
+
+	// `open` holds the lists/maps currently being printed; index assignment
+	// can make a list contain itself, which prints as "[...]" or "{...}".
+	fn stringify_nested(&self, object: &Option<Value>, open: &mut Vec<*const ()>) -> String {
 		match object {
 			None => "nil".to_string(),
 			Some(Value::Nil) => "nil".to_string(),
@@ -60,6 +250,8 @@ impl Interpreter {
 				}
 				text
 			}
+			Some(Value::Int(n)) => n.to_string(),
+			Some(Value::BigInt(n)) => n.to_string(),
 			Some(Value::Str(s)) => s.clone(),
 			Some(Value::Bool(b)) => b.to_string(),
 			Some(Value::Function(f)) => match &f.declaration {
@@ -67,6 +259,37 @@ impl Interpreter {
 				_ => "<fn>".to_string(),
 			},
 			Some(Value::Native(n)) => n.to_string(),
+			Some(Value::Namespace(ns)) => format!("<namespace {}>", ns.name),
+			Some(Value::Iterable(it)) => it.to_string(),
+			Some(Value::Generator(g)) => format!("<generator {}>", g.name),
+			Some(Value::List(items)) => {
+				let ptr = Rc::as_ptr(items) as *const ();
+				if open.contains(&ptr) {
+					return "[...]".to_string();
+				}
+				open.push(ptr);
+				// Quote nested strings so ["a, b"] and ["a", "b"] print differently
+				let parts: Vec<String> = items.borrow().iter().map(|item| match item {
+					Value::Str(s) => format!("\"{}\"", s),
+					other => self.stringify_nested(&Some(other.clone()), open),
+				}).collect();
+				open.pop();
+				format!("[{}]", parts.join(", "))
+			}
+			Some(Value::Error(e)) => e.message.clone(),
+			Some(Value::Map(entries)) => {
+				let ptr = Rc::as_ptr(entries) as *const ();
+				if open.contains(&ptr) {
+					return "{...}".to_string();
+				}
+				open.push(ptr);
+				let parts: Vec<String> = entries.borrow().iter().map(|(key, item)| match item {
+					Value::Str(s) => format!("\"{}\": \"{}\"", key, s),
+					other => format!("\"{}\": {}", key, self.stringify_nested(&Some(other.clone()), open)),
+				}).collect();
+				open.pop();
+				format!("{{{}}}", parts.join(", "))
+			}
 		}
 	}
 
@@ -101,88 +324,7 @@ impl Visitor<Result<Option<Value>, RuntimeError>> for Interpreter {
 		// Evaluate operands
 		let left_val = self.evaluate(&_expr.left)?;
 		let right_val = self.evaluate(&_expr.right)?;
-
-        
-
-		// Helper to extract number
-		let as_number = |v: &Option<Value>| -> Option<f64> {
-			match v {
-				Some(Value::Number(n)) => Some(*n),
-				_ => None,
-			}
-		};
-
-		match _expr.operator.get_type() {
-			TokenType::Minus => {
-				self.check_number_operands(&_expr.operator, &left_val, &right_val)?;
-				let a = as_number(&left_val).unwrap();
-				let b = as_number(&right_val).unwrap();
-				return Ok(Some(Value::Number(a - b)));
-			}
-			TokenType::Slash => {
-				self.check_number_operands(&_expr.operator, &left_val, &right_val)?;
-				let a = as_number(&left_val).unwrap();
-				let b = as_number(&right_val).unwrap();
-				return Ok(Some(Value::Number(a / b)));
-			}
-			TokenType::Star => {
-				self.check_number_operands(&_expr.operator, &left_val, &right_val)?;
-				let a = as_number(&left_val).unwrap();
-				let b = as_number(&right_val).unwrap();
-				return Ok(Some(Value::Number(a * b)));
-			}
-			TokenType::Plus => {
-				// Number + Number
-				if let (Some(a), Some(b)) = (as_number(&left_val), as_number(&right_val)) {
-					return Ok(Some(Value::Number(a + b)));
-				}
-
-				// If either operand is a string, convert both to strings and concatenate.
-				if matches!(left_val, Some(Value::Str(_))) || matches!(right_val, Some(Value::Str(_))) {
-					let left_s = self.stringify(&left_val);
-					let right_s = self.stringify(&right_val);
-					return Ok(Some(Value::Str(format!("{}{}", left_s, right_s))));
-				}
-
-				// Otherwise it's a type error
-				return Err(RuntimeError::new(_expr.operator.clone(), "Operands must be two numbers or two strings."));
-			}
-			TokenType::Greater => {
-				self.check_number_operands(&_expr.operator, &left_val, &right_val)?;
-				let a = as_number(&left_val).unwrap();
-				let b = as_number(&right_val).unwrap();
-				return Ok(Some(Value::Bool(a > b)));
-			}
-			TokenType::GreaterEqual => {
-				self.check_number_operands(&_expr.operator, &left_val, &right_val)?;
-				let a = as_number(&left_val).unwrap();
-				let b = as_number(&right_val).unwrap();
-				return Ok(Some(Value::Bool(a >= b)));
-			}
-			TokenType::Less => {
-				self.check_number_operands(&_expr.operator, &left_val, &right_val)?;
-				let a = as_number(&left_val).unwrap();
-				let b = as_number(&right_val).unwrap();
-				return Ok(Some(Value::Bool(a < b)));
-			}
-			TokenType::LessEqual => {
-				self.check_number_operands(&_expr.operator, &left_val, &right_val)?;
-				let a = as_number(&left_val).unwrap();
-				let b = as_number(&right_val).unwrap();
-				return Ok(Some(Value::Bool(a <= b)));
-			}
-			TokenType::BangEqual => {
-				return Ok(Some(Value::Bool(!Interpreter::is_equal(&left_val, &right_val))));
-			}
-			TokenType::EqualEqual => {
-				return Ok(Some(Value::Bool(Interpreter::is_equal(&left_val, &right_val))));
-			}
-				_ => {
-				// Unsupported operator
-				crate::util::logger::global_logger().log(LogLevel::Error, "interpreter: Unsupported binary operator.");
-				return Ok(None);
-			}
-		}
+		self.apply_binary(&_expr.operator, &left_val, &right_val)
 	}
 
 
@@ -196,6 +338,26 @@ impl Visitor<Result<Option<Value>, RuntimeError>> for Interpreter {
 		}
 	}
 
+	fn visit_destructure_assign_expr(&mut self, expr: &DestructureAssignExpr) -> Result<Option<Value>, RuntimeError> {
+		// The value is taken apart and every target checked before anything
+		// is assigned, so `[a, b] = [b, a]` swaps, and a shape error or an
+		// undefined or constant target assigns nothing
+		let value = self.evaluate(&expr.value)?.unwrap_or(Value::Nil);
+		let mut parts: Vec<(Token, Value)> = Vec::new();
+		Interpreter::destructure(&expr.pattern, &value, &mut parts)?;
+		for (name, _) in &parts {
+			if let Err(msg) = self.environment.borrow().check_assignable(name) {
+				return Err(RuntimeError::new(name.clone(), &msg));
+			}
+		}
+		for (name, part) in parts {
+			if let Err(msg) = self.environment.borrow_mut().assign(&name, Some(part)) {
+				return Err(RuntimeError::new(name, &msg));
+			}
+		}
+		Ok(Some(value))
+	}
+
 	fn visit_logical_expr(&mut self, expr: &LogicalExpr) -> Result<Option<Value>, RuntimeError> {
 		let left = self.evaluate(&expr.left)?;
 		match expr.operator.get_type() {
@@ -209,6 +371,10 @@ impl Visitor<Result<Option<Value>, RuntimeError>> for Interpreter {
 					return Ok(left);
 				}
 			}
+			// `??` only falls through to the right side for nil
+			TokenType::QuestionQuestion if !matches!(left, None | Some(Value::Nil)) => {
+				return Ok(left);
+			}
 			_ => {}
 		}
 		// Not short-circuited; evaluate and return right
@@ -226,6 +392,8 @@ impl Visitor<Result<Option<Value>, RuntimeError>> for Interpreter {
 		match &expr.value {
 			None => Ok(Some(Value::Nil)),
 			Some(crate::parse::expr::LiteralValue::Number(n)) => Ok(Some(Value::Number(*n))),
+			Some(crate::parse::expr::LiteralValue::Int(n)) => Ok(Some(Value::Int(*n))),
+			Some(crate::parse::expr::LiteralValue::BigInt(n)) => Ok(Some(Value::BigInt(Rc::new(n.clone())))),
 			Some(crate::parse::expr::LiteralValue::Str(s)) => Ok(Some(Value::Str(s.clone()))),
 			Some(crate::parse::expr::LiteralValue::Bool(b)) => Ok(Some(Value::Bool(*b))),
 		}
@@ -236,6 +404,13 @@ impl Visitor<Result<Option<Value>, RuntimeError>> for Interpreter {
 		match _expr.operator.get_type() {
 			TokenType::Minus => {
 				self.check_number_operand(&_expr.operator, &right)?;
+				// -i64::MIN doesn't fit in an i64, and -(2^63) fits again
+				if let Some(Value::Int(n)) = right {
+					return Ok(Some(Value::from_bigint(BigInt::from_i64(n).neg())));
+				}
+				if let Some(Value::BigInt(n)) = &right {
+					return Ok(Some(Value::from_bigint(n.neg())));
+				}
 				if let Some(Value::Number(n)) = right {
 					return Ok(Some(Value::Number(-n)));
 				}
@@ -245,6 +420,10 @@ impl Visitor<Result<Option<Value>, RuntimeError>> for Interpreter {
 			TokenType::Bang => {
 				return Ok(Some(Value::Bool(!Interpreter::is_truthy(&right))));
 			}
+			TokenType::Tilde => {
+				let n = self.integral_operand(&_expr.operator, &right)?;
+				Ok(Some(Value::Int(!n)))
+			}
 			_ => {
 				crate::util::logger::global_logger().log(LogLevel::Error, "interpreter: Unsupported unary operator.");
 				return Ok(None);
@@ -254,49 +433,74 @@ impl Visitor<Result<Option<Value>, RuntimeError>> for Interpreter {
 
 		fn visit_variable_expr(&mut self, name: &Token) -> Result<Option<Value>, RuntimeError> {
 			match self.environment.borrow().get(name) {
+				Ok(None) if self.strict => Err(RuntimeError::new(name.clone(), &format!("Variable '{}' used before initialization.", name.lexeme))),
 				Ok(val) => Ok(val),
 				Err(msg) => Err(RuntimeError::new(name.clone(), &msg)),
 			}
 		}
 
 		fn visit_call_expr(&mut self, expr: &crate::parse::expr::CallExpr) -> Result<Option<Value>, RuntimeError> {
-			// Evaluate callee
-			let callee_val = self.evaluate(&expr.callee)?;
-
-			// Evaluate arguments
-			let mut arguments: Vec<Value> = Vec::new();
-			for arg_expr in &expr.arguments {
-				let v = self.evaluate(arg_expr)?;
-				// convert Option<Value> to Value, treating None as Nil
-				let val = match v {
-					Some(vv) => vv,
-					None => Value::Nil,
-				};
-				arguments.push(val);
+			Ok(self.call_link(expr)?.unwrap_or(Some(Value::Nil)))
+		}
+
+		fn visit_get_expr(&mut self, expr: &GetExpr) -> Result<Option<Value>, RuntimeError> {
+			Ok(self.get_link(expr)?.unwrap_or(Some(Value::Nil)))
+		}
+
+		fn visit_list_expr(&mut self, expr: &ListExpr) -> Result<Option<Value>, RuntimeError> {
+			let mut items: Vec<Value> = Vec::new();
+			for element in &expr.elements {
+				// Uninitialized values are stored as nil
+				items.push(self.evaluate(element)?.unwrap_or(Value::Nil));
 			}
+			Ok(Some(Value::List(Rc::new(RefCell::new(items)))))
+		}
 
-			// Ensure callee is callable (user-defined or native)
-			match callee_val {
-				Some(Value::Function(func_rc)) => {
-					let func = func_rc.as_ref();
-					// arity check
-					if arguments.len() != func.arity() {
-						return Err(RuntimeError::new(expr.paren.clone(), &format!("Expected {} arguments but got {}.", func.arity(), arguments.len())));
-					}
-					// Call the function
-					return func.call(self, &arguments);
-				}
-				Some(Value::Native(native_rc)) => {
-					// arity check
-					if arguments.len() != native_rc.arity() {
-						return Err(RuntimeError::new(expr.paren.clone(), &format!("Expected {} arguments but got {}.", native_rc.arity(), arguments.len())));
-					}
-					return native_rc.call(self, &arguments);
-				}
-				_ => {
-					return Err(RuntimeError::new(expr.paren.clone(), "Can only call functions and classes."));
-				}
+		fn visit_index_expr(&mut self, expr: &IndexExpr) -> Result<Option<Value>, RuntimeError> {
+			Ok(self.index_link(expr)?.unwrap_or(Some(Value::Nil)))
+		}
+
+		fn visit_set_index_expr(&mut self, expr: &SetIndexExpr) -> Result<Option<Value>, RuntimeError> {
+			let object = self.evaluate(&expr.object)?;
+			let index = self.evaluate(&expr.index)?;
+			let value = self.evaluate(&expr.value)?;
+			let place = Place::Index { object, index, bracket: expr.bracket.clone() };
+			self.write_place(&place, value.clone())?;
+			Ok(value)
+		}
+
+		fn visit_compound_assign_expr(&mut self, expr: &CompoundAssignExpr) -> Result<Option<Value>, RuntimeError> {
+			// Resolve the target first so `xs[f()] += 1` only calls f once
+			let place = self.resolve_place(&expr.target)?;
+			let current = self.read_place(&place)?;
+			let operand = self.evaluate(&expr.value)?;
+			let binary_type = match expr.operator.get_type() {
+				TokenType::PlusEqual => TokenType::Plus,
+				TokenType::MinusEqual => TokenType::Minus,
+				TokenType::StarEqual => TokenType::Star,
+				TokenType::SlashEqual => TokenType::Slash,
+				_ => TokenType::Percent,
+			};
+			// Errors are reported at the compound operator, e.g. "+=" for a bad "+"
+			let operator = Token::new_token(binary_type, expr.operator.lexeme.clone(), None, expr.operator.line);
+			let result = self.apply_binary(&operator, &current, &operand)?;
+			self.write_place(&place, result.clone())?;
+			Ok(result)
+		}
+
+		fn visit_increment_expr(&mut self, expr: &IncrementExpr) -> Result<Option<Value>, RuntimeError> {
+			let place = self.resolve_place(&expr.target)?;
+			let current = self.read_place(&place)?;
+			// `x++` is `x + 1`, so ints stay ints and overflow is reported
+			let binary_type = if expr.operator.get_type() == TokenType::PlusPlus { TokenType::Plus } else { TokenType::Minus };
+			let operator = Token::new_token(binary_type, expr.operator.lexeme.clone(), None, expr.operator.line);
+			if current.as_ref().and_then(|v| v.as_f64()).is_none() {
+				return Err(RuntimeError::new(expr.operator.clone(), "Operand must be a number."));
 			}
+			let new = self.apply_binary(&operator, &current, &Some(Value::Int(1)))?;
+			self.write_place(&place, new.clone())?;
+			// Prefix yields the updated value, postfix the original one
+			Ok(if expr.prefix { new } else { current })
 		}
 }
 impl StmtVisitor<Result<(), RuntimeError>> for Interpreter {
@@ -311,7 +515,8 @@ impl StmtVisitor<Result<(), RuntimeError>> for Interpreter {
 		println!("{}", self.stringify(&val));
 		Ok(())
 	}
-	fn visit_var_stmt(&mut self, name: &Token, initializer: &Option<Expr>) -> Result<(), RuntimeError> {
+	// Type annotations are only used by the checker before running
+	fn visit_var_stmt(&mut self, name: &Token, _ty: &Option<Type>, initializer: &Option<Expr>) -> Result<(), RuntimeError> {
 		let value = match initializer {
 			Some(expr) => self.evaluate(expr)?,
 			None => None,
@@ -320,22 +525,45 @@ impl StmtVisitor<Result<(), RuntimeError>> for Interpreter {
 		Ok(())
 	}
 
+	fn visit_var_pattern_stmt(&mut self, pattern: &Pattern, initializer: &Expr) -> Result<(), RuntimeError> {
+		let value = self.evaluate(initializer)?.unwrap_or(Value::Nil);
+		let mut parts: Vec<(Token, Value)> = Vec::new();
+		Interpreter::destructure(pattern, &value, &mut parts)?;
+		for (name, part) in parts {
+			self.environment.borrow_mut().define(&name.lexeme, Some(part));
+		}
+		Ok(())
+	}
+
+	fn visit_const_stmt(&mut self, name: &Token, _ty: &Option<Type>, initializer: &Expr) -> Result<(), RuntimeError> {
+		let value = self.evaluate(initializer)?;
+		self.environment.borrow_mut().define_const(&name.lexeme, value);
+		Ok(())
+	}
+
 	fn visit_return_stmt(&mut self, _keyword: &Token, value: &Option<Expr>) -> Result<(), RuntimeError> {
-		// Evaluate return value if present, otherwise treat as nil
+		// Evaluate return value if present, otherwise treat as nil. Strict
+		// mode returns a real nil so the result doesn't look uninitialized.
 		let ret_val = match value {
 			Some(expr) => self.evaluate(expr)?,
+			None if self.strict => Some(Value::Nil),
 			None => None,
 		};
 
 		// Store the return value in thread-local storage and panic with a simple marker
 		crate::interpret::return_value::set_return(ret_val);
-		const RETURN_MARKER: &str = "__LOX_RETURN__";
-		std::panic::panic_any(RETURN_MARKER);
+		std::panic::panic_any(crate::interpret::return_value::RETURN_MARKER);
+	}
+
+	// Generator bodies are run by interpret::generator, which handles yield
+	// itself; reaching one here means it is outside a generator.
+	fn visit_yield_stmt(&mut self, keyword: &Token, _value: &Option<Expr>) -> Result<(), RuntimeError> {
+		Err(RuntimeError::new(keyword.clone(), "Can't use 'yield' outside a generator function."))
 	}
 
-	fn visit_function_stmt(&mut self, name: &Token, params: &Vec<Token>, body: &Vec<Stmt>) -> Result<(), RuntimeError> {
+	fn visit_function_stmt(&mut self, name: &Token, params: &Vec<Param>, rest: &Option<Token>, return_type: &Option<Type>, body: &Vec<Stmt>, generator: bool) -> Result<(), RuntimeError> {
 		// Wrap the parsed function declaration into a runtime LoxFunction object
-	let decl = Stmt::Function { name: name.clone(), params: params.clone(), body: body.clone() };
+	let decl = Stmt::Function { name: name.clone(), params: params.clone(), rest: rest.clone(), return_type: return_type.clone(), body: body.clone(), generator };
 	let func = crate::interpret::lox_function::LoxFunction::new(decl, self.environment.clone());
 		let rc = Rc::new(func);
 		self.environment.borrow_mut().define(&name.lexeme, Some(Value::Function(rc)));
@@ -363,11 +591,80 @@ impl StmtVisitor<Result<(), RuntimeError>> for Interpreter {
 		Ok(())
 	}
 
+	fn visit_for_in_stmt(&mut self, name: &Token, iterable: &Expr, body: &Stmt) -> Result<(), RuntimeError> {
+		let value = self.evaluate(iterable)?.unwrap_or(Value::Nil);
+		let mut iterator = crate::interpret::iterator::iterate(&value, name)?;
+		while let Some(item) = iterator.next(self)? {
+			// A new environment per iteration, so closures created in the body
+			// each keep their own value of the loop variable
+			let env = Rc::new(RefCell::new(Environment::new_enclosing(self.environment.clone())));
+			env.borrow_mut().define(&name.lexeme, Some(item));
+			self.execute_block(std::slice::from_ref(body), env)?;
+		}
+		Ok(())
+	}
+
 	fn visit_block_stmt(&mut self, statements: &Vec<Stmt>) -> Result<(), RuntimeError> {
 		// Create a new environment that encloses the current one and execute the block
 		let new_env = Rc::new(RefCell::new(Environment::new_enclosing(self.environment.clone())));
 		self.execute_block(statements, new_env)
 	}
+
+	fn visit_throw_stmt(&mut self, keyword: &Token, value: &Expr) -> Result<(), RuntimeError> {
+		let value = self.evaluate(value)?.unwrap_or(Value::Nil);
+		// Rethrowing a caught error keeps its original message
+		let message = match &value {
+			Value::Error(e) => e.message.clone(),
+			other => format!("Uncaught exception: {}", self.stringify(&Some(other.clone()))),
+		};
+		Err(RuntimeError::thrown(keyword.clone(), &message, value))
+	}
+
+	fn visit_match_stmt(&mut self, _keyword: &Token, subject: &Expr, arms: &[MatchArm]) -> Result<(), RuntimeError> {
+		let value = self.evaluate(subject)?.unwrap_or(Value::Nil);
+		if let Some((index, env)) = self.select_arm(&value, arms, self.environment.clone())? {
+			self.execute_block(std::slice::from_ref(&arms[index].body), env)?;
+		}
+		Ok(())
+	}
+
+	fn visit_import_stmt(&mut self, keyword: &Token, path: &Token, name: &Token) -> Result<(), RuntimeError> {
+		let module = self.import_module(keyword, &path.lexeme)?;
+		self.environment.borrow_mut().define(&name.lexeme, Some(module));
+		Ok(())
+	}
+
+	fn visit_try_stmt(&mut self, body: &[Stmt], catch_name: &Option<Token>, catch_body: Option<&[Stmt]>, finally_body: Option<&[Stmt]>) -> Result<(), RuntimeError> {
+		let env = Rc::new(RefCell::new(Environment::new_enclosing(self.environment.clone())));
+		let mut completion = self.execute_block_completion(body, env);
+
+		// An error from the try block is handed to the catch block, if any
+		if let (Completion::Normal(Err(error)), Some(name), Some(handler)) = (&completion, catch_name, catch_body) {
+			let caught = match &error.value {
+				Some(v) => v.clone(),
+				None => Value::Error(Rc::new(ErrorValue { message: error.message.clone(), line: error.token.line })),
+			};
+			let env = Rc::new(RefCell::new(Environment::new_enclosing(self.environment.clone())));
+			env.borrow_mut().define(&name.lexeme, Some(caught));
+			completion = self.execute_block_completion(handler, env);
+		}
+
+		// finally runs however the blocks above finished. If it fails itself,
+		// that error replaces whatever was pending.
+		if let Some(cleanup) = finally_body {
+			let env = Rc::new(RefCell::new(Environment::new_enclosing(self.environment.clone())));
+			self.execute_block(cleanup, env)?;
+		}
+
+		match completion {
+			Completion::Normal(result) => result,
+			Completion::Return(value) => {
+				// Resume the pending return
+				crate::interpret::return_value::set_return(value);
+				std::panic::panic_any(crate::interpret::return_value::RETURN_MARKER);
+			}
+		}
+	}
 }
 
 impl Interpreter {
@@ -375,20 +672,372 @@ impl Interpreter {
 		expr.accept(self)
 	}
 
-	pub(crate) fn execute_block(&mut self, statements: &Vec<Stmt>, env: Rc<RefCell<Environment>>) -> Result<(), RuntimeError> {
+	// Postfix links (calls, property accesses and indexing) evaluate their
+	// object through this. Ok(None) means a `?.` earlier in the same chain
+	// saw nil, so the links after it are skipped and the chain gives nil.
+	fn chain_link(&mut self, expr: &Expr) -> Result<Option<Option<Value>>, RuntimeError> {
+		match expr {
+			Expr::Call(call) => self.call_link(call),
+			Expr::Get(get) => self.get_link(get),
+			Expr::Index(index) => self.index_link(index),
+			_ => Ok(Some(self.evaluate(expr)?)),
+		}
+	}
+
+	fn call_link(&mut self, expr: &crate::parse::expr::CallExpr) -> Result<Option<Option<Value>>, RuntimeError> {
+		let Some(callee) = self.chain_link(&expr.callee)? else {
+			return Ok(None);
+		};
+		// `f?.()` on nil skips the arguments and the call
+		if expr.optional && matches!(callee, None | Some(Value::Nil)) {
+			return Ok(None);
+		}
+		Ok(Some(self.call_value(expr, callee)?))
+	}
+
+	fn call_value(&mut self, expr: &crate::parse::expr::CallExpr, callee_val: Option<Value>) -> Result<Option<Value>, RuntimeError> {
+		// Evaluate arguments
+		let mut arguments: Vec<Value> = Vec::new();
+		for arg_expr in &expr.arguments {
+			let v = self.evaluate(arg_expr)?;
+			// convert Option<Value> to Value, treating None as Nil
+			let val = match v {
+				Some(vv) => vv,
+				None => Value::Nil,
+			};
+			arguments.push(val);
+		}
+		let mut named: Vec<(Token, Value)> = Vec::new();
+		for (name, value_expr) in &expr.named {
+			let value = self.evaluate(value_expr)?.unwrap_or(Value::Nil);
+			named.push((name.clone(), value));
+		}
+
+		// Ensure callee is callable (user-defined or native)
+		match callee_val {
+			Some(Value::Function(func_rc)) => {
+				let func = func_rc.as_ref();
+				// arity check; named arguments count towards it
+				let count = arguments.len() + named.len();
+				if !func.arity().accepts(count) {
+					return Err(RuntimeError::new(expr.paren.clone(), &format!("Expected {} but got {}.", func.arity(), count)));
+				}
+				// Call the function
+				func.call_with_named(self, &expr.paren, &arguments, &named)
+			}
+			Some(Value::Native(native_rc)) => {
+				if let Some((name, _)) = named.first() {
+					return Err(RuntimeError::new(name.clone(), "Native functions don't take named arguments."));
+				}
+				// arity check (some natives take optional trailing arguments)
+				if !native_rc.arity().accepts(arguments.len()) {
+					return Err(RuntimeError::new(expr.paren.clone(), &format!("Expected {} but got {}.", native_rc.arity(), arguments.len())));
+				}
+				native_rc.call(self, &expr.paren, &arguments)
+			}
+			_ => Err(RuntimeError::new(expr.paren.clone(), "Can only call functions and classes.")),
+		}
+	}
+
+	fn get_link(&mut self, expr: &GetExpr) -> Result<Option<Option<Value>>, RuntimeError> {
+		let Some(object) = self.chain_link(&expr.object)? else {
+			return Ok(None);
+		};
+		if expr.optional && matches!(object, None | Some(Value::Nil)) {
+			return Ok(None);
+		}
+		Ok(Some(self.get_property(expr, object)?))
+	}
+
+	fn get_property(&mut self, expr: &GetExpr, object: Option<Value>) -> Result<Option<Value>, RuntimeError> {
+		match object {
+			Some(Value::Namespace(ns)) => match ns.get(&expr.name.lexeme) {
+				Some(v) => Ok(Some(v)),
+				None => Err(RuntimeError::new(expr.name.clone(), &format!("Undefined property '{}' in namespace '{}'.", expr.name.lexeme, ns.name))),
+			},
+			Some(Value::Map(entries)) => match entries.borrow().get(&expr.name.lexeme) {
+				Some(v) => Ok(Some(v.clone())),
+				None => Err(RuntimeError::new(expr.name.clone(), &format!("Undefined key '{}'.", expr.name.lexeme))),
+			},
+			Some(Value::Error(e)) => match expr.name.lexeme.as_str() {
+				"message" => Ok(Some(Value::Str(e.message.clone()))),
+				"line" => Ok(Some(Value::Int(e.line as i64))),
+				_ => Err(RuntimeError::new(expr.name.clone(), &format!("Errors have no property '{}'.", expr.name.lexeme))),
+			},
+			// gen.next() resumes the generator
+			Some(Value::Generator(g)) => match expr.name.lexeme.as_str() {
+				"next" => Ok(Some(Value::Native(Rc::new(GeneratorNext(g))))),
+				_ => Err(RuntimeError::new(expr.name.clone(), &format!("Generators have no property '{}'.", expr.name.lexeme))),
+			},
+			_ => Err(RuntimeError::new(expr.name.clone(), "Only namespaces, maps, errors and generators have properties.")),
+		}
+	}
+
+	fn index_link(&mut self, expr: &IndexExpr) -> Result<Option<Option<Value>>, RuntimeError> {
+		let Some(object) = self.chain_link(&expr.object)? else {
+			return Ok(None);
+		};
+		Ok(Some(self.index_value(expr, object)?))
+	}
+
+	fn index_value(&mut self, expr: &IndexExpr, object: Option<Value>) -> Result<Option<Value>, RuntimeError> {
+		let index = self.evaluate(&expr.index)?;
+		match object {
+			Some(Value::List(items)) => {
+				let items = items.borrow();
+				let i = self.check_index(&expr.bracket, &index, items.len())?;
+				Ok(Some(items[i].clone()))
+			}
+			Some(Value::Str(s)) => {
+				// Strings index by character, not by byte
+				let i = self.check_index(&expr.bracket, &index, s.chars().count())?;
+				Ok(Some(Value::Str(s.chars().nth(i).unwrap().to_string())))
+			}
+			Some(Value::Map(entries)) => match &index {
+				Some(Value::Str(key)) => match entries.borrow().get(key) {
+					Some(v) => Ok(Some(v.clone())),
+					None => Err(RuntimeError::new(expr.bracket.clone(), &format!("Undefined key '{}'.", key))),
+				},
+				_ => Err(RuntimeError::new(expr.bracket.clone(), "Map keys must be strings.")),
+			},
+			_ => Err(RuntimeError::new(expr.bracket.clone(), "Only lists, strings and maps can be indexed.")),
+		}
+	}
+
+	// Evaluate an expression with `env` as the current environment, e.g. a
+	// parameter default inside the call's environment.
+	pub(crate) fn evaluate_in(&mut self, expr: &Expr, env: Rc<RefCell<Environment>>) -> Result<Option<Value>, RuntimeError> {
+		let previous = std::mem::replace(&mut self.environment, env);
+		let result = self.evaluate(expr);
+		self.environment = previous;
+		result
+	}
+
+	// Index of the first arm whose pattern matches `value` and whose guard
+	// holds, with a new environment (enclosing `enclosing`) holding the
+	// names the pattern bound.
+	pub(crate) fn select_arm(&mut self, value: &Value, arms: &[MatchArm], enclosing: Rc<RefCell<Environment>>) -> Result<Option<SelectedArm>, RuntimeError> {
+		for (index, arm) in arms.iter().enumerate() {
+			let env = Rc::new(RefCell::new(Environment::new_enclosing(enclosing.clone())));
+			if !self.match_pattern(&arm.pattern, value, &env)? {
+				continue;
+			}
+			if let Some(guard) = &arm.guard {
+				let passed = self.evaluate_in(guard, env.clone())?;
+				if !Interpreter::is_truthy(&passed) {
+					continue;
+				}
+			}
+			return Ok(Some((index, env)));
+		}
+		Ok(None)
+	}
+
+	// Test `value` against a pattern, defining its bindings in `env`
+	fn match_pattern(&mut self, pattern: &Pattern, value: &Value, env: &Rc<RefCell<Environment>>) -> Result<bool, RuntimeError> {
+		match pattern {
+			Pattern::Wildcard(_) => Ok(true),
+			Pattern::Binding(name) => {
+				env.borrow_mut().define(&name.lexeme, Some(value.clone()));
+				Ok(true)
+			}
+			Pattern::Literal { value: literal, .. } => {
+				let expected = self.evaluate(literal)?.unwrap_or(Value::Nil);
+				Ok(Interpreter::is_equal(&Some(expected), &Some(value.clone())))
+			}
+			Pattern::List { elements, rest, .. } => {
+				let items = match value {
+					Value::List(items) => items.borrow().clone(),
+					_ => return Ok(false),
+				};
+				let fits = match rest {
+					Some(_) => items.len() >= elements.len(),
+					None => items.len() == elements.len(),
+				};
+				if !fits {
+					return Ok(false);
+				}
+				for (element, item) in elements.iter().zip(&items) {
+					if !self.match_pattern(element, item, env)? {
+						return Ok(false);
+					}
+				}
+				if let Some(rest) = rest.as_ref().filter(|r| r.lexeme != "_") {
+					let remaining = items[elements.len()..].to_vec();
+					env.borrow_mut().define(&rest.lexeme, Some(Value::List(Rc::new(RefCell::new(remaining)))));
+				}
+				Ok(true)
+			}
+			Pattern::Map { entries, .. } => {
+				let map = match value {
+					Value::Map(map) => map.borrow().clone(),
+					_ => return Ok(false),
+				};
+				for (key, element) in entries {
+					match map.get(&key.lexeme) {
+						Some(item) if self.match_pattern(element, item, env)? => {}
+						_ => return Ok(false),
+					}
+				}
+				Ok(true)
+			}
+			// Each alternative binds into a scratch scope so one that fails part
+			// way doesn't leave its bindings behind
+			Pattern::Or(alternatives) => {
+				for alternative in alternatives {
+					let scratch = Rc::new(RefCell::new(Environment::new()));
+					if self.match_pattern(alternative, value, &scratch)? {
+						for (name, bound) in scratch.borrow().bindings() {
+							env.borrow_mut().define(&name, bound);
+						}
+						return Ok(true);
+					}
+				}
+				Ok(false)
+			}
+		}
+	}
+
+	// Take `value` apart by a destructuring pattern, collecting the name and
+	// value for each binding. A value of the wrong shape is an error at the
+	// part of the pattern it doesn't fit.
+	fn destructure(pattern: &Pattern, value: &Value, parts: &mut Vec<(Token, Value)>) -> Result<(), RuntimeError> {
+		match pattern {
+			Pattern::Wildcard(_) => Ok(()),
+			Pattern::Binding(name) => {
+				parts.push((name.clone(), value.clone()));
+				Ok(())
+			}
+			Pattern::List { bracket, elements, rest } => {
+				let items = match value {
+					Value::List(items) => items.borrow().clone(),
+					other => return Err(RuntimeError::new(bracket.clone(), &format!("Expected a list to destructure, got {}.", other.type_name()))),
+				};
+				match rest {
+					Some(_) if items.len() < elements.len() => {
+						return Err(RuntimeError::new(bracket.clone(), &format!("Expected at least {} but got {}.", plural(elements.len(), "element"), items.len())));
+					}
+					None if items.len() != elements.len() => {
+						return Err(RuntimeError::new(bracket.clone(), &format!("Expected {} but got {}.", plural(elements.len(), "element"), items.len())));
+					}
+					_ => {}
+				}
+				for (element, item) in elements.iter().zip(&items) {
+					Interpreter::destructure(element, item, parts)?;
+				}
+				if let Some(rest) = rest.as_ref().filter(|r| r.lexeme != "_") {
+					let remaining = items[elements.len()..].to_vec();
+					parts.push((rest.clone(), Value::List(Rc::new(RefCell::new(remaining)))));
+				}
+				Ok(())
+			}
+			Pattern::Map { brace, entries } => {
+				let map = match value {
+					Value::Map(map) => map.borrow().clone(),
+					other => return Err(RuntimeError::new(brace.clone(), &format!("Expected a map to destructure, got {}.", other.type_name()))),
+				};
+				for (key, element) in entries {
+					match map.get(&key.lexeme) {
+						Some(item) => Interpreter::destructure(element, item, parts)?,
+						None => return Err(RuntimeError::new(key.clone(), &format!("Missing key '{}'.", key.lexeme))),
+					}
+				}
+				Ok(())
+			}
+			// The parser only builds destructuring patterns without these
+			Pattern::Literal { token, .. } => Err(RuntimeError::new(token.clone(), "Only names, '_', lists and maps can be destructured.")),
+			Pattern::Or(_) => Err(RuntimeError::new(pattern.token().clone(), "Only names, '_', lists and maps can be destructured.")),
+		}
+	}
+
+	// Namespace of the module at `path`, running it first if this is its
+	// first import. Modules run in their own scope inside the globals with
+	// the module's file as the base for its own imports.
+	fn import_module(&mut self, keyword: &Token, path: &str) -> Result<Value, RuntimeError> {
+		let resolved = crate::interpret::module::resolve(self.script_path.as_deref(), path)
+			.map_err(|msg| RuntimeError::new(keyword.clone(), &msg))?;
+		if let Some(module) = self.modules.get(&resolved) {
+			return Ok(module);
+		}
+		if let Some(cycle) = self.modules.cycle(&resolved) {
+			// The chain already names every module involved
+			let mut error = RuntimeError::new(keyword.clone(), &format!("Import cycle: {}.", cycle));
+			error.in_module = true;
+			return Err(error);
+		}
+		let statements = crate::interpret::module::parse(&resolved)
+			.map_err(|msg| RuntimeError::new(keyword.clone(), &format!("Can't import '{}': {}", path, msg)))?;
+
+		let env = Rc::new(RefCell::new(Environment::new_enclosing(self.globals.clone())));
+		let importer = self.script_path.replace(resolved.clone());
+		self.modules.start(resolved.clone());
+		// A top-level `return` stops the module there, like reaching its end.
+		// Any other panic still has to leave the importer's state as it was.
+		use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+		let quiet = crate::interpret::return_value::QuietPanics::install();
+		let outcome = catch_unwind(AssertUnwindSafe(|| self.execute_block_completion(&statements, env.clone())));
+		drop(quiet);
+		self.script_path = importer;
+		let result = match outcome {
+			Ok(Completion::Normal(result)) => result,
+			Ok(Completion::Return(_)) => Ok(()),
+			Err(payload) => {
+				self.modules.finish(None);
+				resume_unwind(payload);
+			}
+		};
+
+		// A failed module isn't cached, so importing it again reruns it.
+		// Only the innermost module is named in the message.
+		if let Err(mut error) = result {
+			self.modules.finish(None);
+			if !error.in_module {
+				error.message = format!("{} (in module '{}')", error.message, path);
+				error.in_module = true;
+			}
+			return Err(error);
+		}
+		let module = crate::interpret::module::namespace(&resolved, &env.borrow());
+		self.modules.finish(Some(module.clone()));
+		Ok(module)
+	}
+
+	pub(crate) fn execute_block(&mut self, statements: &[Stmt], env: Rc<RefCell<Environment>>) -> Result<(), RuntimeError> {
+		use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
 		let previous = self.environment.clone();
 		self.environment = env;
-		let result = (|| -> Result<(), RuntimeError> {
+		// A Lox `return` unwinds through here as a panic; restore the
+		// environment before passing it on so the caller doesn't keep running
+		// in the function's scope.
+		let result = catch_unwind(AssertUnwindSafe(|| -> Result<(), RuntimeError> {
 			for stmt in statements {
 				self.execute(stmt)?;
 			}
 			Ok(())
-		})();
+		}));
 		self.environment = previous;
-		result
+		match result {
+			Ok(result) => result,
+			Err(payload) => resume_unwind(payload),
+		}
 	}
 
-	fn is_truthy(val: &Option<Value>) -> bool {
+	// Like execute_block, but also stops a Lox `return` unwinding through the
+	// block so the caller can run cleanup code before resuming it.
+	fn execute_block_completion(&mut self, statements: &[Stmt], env: Rc<RefCell<Environment>>) -> Completion {
+		use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+		match catch_unwind(AssertUnwindSafe(|| self.execute_block(statements, env))) {
+			Ok(result) => Completion::Normal(result),
+			Err(payload) => {
+				if let Some(s) = payload.downcast_ref::<&str>() && *s == crate::interpret::return_value::RETURN_MARKER {
+					return Completion::Return(crate::interpret::return_value::take_return());
+				}
+				resume_unwind(payload);
+			}
+		}
+	}
+
+	pub fn is_truthy(val: &Option<Value>) -> bool {
 		match val {
 			None => false,
 			Some(Value::Bool(b)) => *b,
@@ -397,31 +1046,266 @@ impl Interpreter {
 		}
 	}
 
-	fn is_equal(a: &Option<Value>, b: &Option<Value>) -> bool {
+	pub fn is_equal(a: &Option<Value>, b: &Option<Value>) -> bool {
 		match (a, b) {
 			(None, None) => true,
 			(None, Some(_)) | (Some(_), None) => false,
-			(Some(Value::Nil), Some(Value::Nil)) => true,
-			(Some(Value::Number(x)), Some(Value::Number(y))) => x == y,
-			(Some(Value::Str(x)), Some(Value::Str(y))) => x == y,
-			(Some(Value::Bool(x)), Some(Value::Bool(y))) => x == y,
-			(Some(Value::Function(f1)), Some(Value::Function(f2))) => std::rc::Rc::ptr_eq(f1, f2),
-			_ => false,
+			// Equality agrees with the total ordering, so NaN == NaN and 1 == 1.0
+			(Some(x), Some(y)) => compare_values(x, y) == Ordering::Equal,
 		}
 	}
 
-	fn check_number_operand(&self, operator: &Token, operand: &Option<Value>) -> Result<(), RuntimeError> {
-		match operand {
-			Some(Value::Number(_)) => Ok(()),
-			_ => Err(RuntimeError::new(operator.clone(), "Operand must be a number.")),
+	// Evaluate the parts of an assignment target. The parser only builds
+	// compound assignments and increments around variables and index expressions.
+	fn resolve_place(&mut self, target: &Expr) -> Result<Place, RuntimeError> {
+		match target {
+			Expr::Variable(name) => Ok(Place::Variable(name.clone())),
+			Expr::Index(index_expr) => {
+				let object = self.evaluate(&index_expr.object)?;
+				let index = self.evaluate(&index_expr.index)?;
+				Ok(Place::Index { object, index, bracket: index_expr.bracket.clone() })
+			}
+			_ => unreachable!("parser only produces variable and index targets"),
+		}
+	}
+
+	fn read_place(&mut self, place: &Place) -> Result<Option<Value>, RuntimeError> {
+		match place {
+			Place::Variable(name) => self.visit_variable_expr(name),
+			Place::Index { object, index, bracket } => match object {
+				Some(Value::List(items)) => {
+					let items = items.borrow();
+					let i = self.check_index(bracket, index, items.len())?;
+					Ok(Some(items[i].clone()))
+				}
+				Some(Value::Map(entries)) => match index {
+					Some(Value::Str(key)) => match entries.borrow().get(key) {
+						Some(v) => Ok(Some(v.clone())),
+						None => Err(RuntimeError::new(bracket.clone(), &format!("Undefined key '{}'.", key))),
+					},
+					_ => Err(RuntimeError::new(bracket.clone(), "Map keys must be strings.")),
+				},
+				Some(Value::Str(_)) => Err(RuntimeError::new(bracket.clone(), "Strings are immutable.")),
+				_ => Err(RuntimeError::new(bracket.clone(), "Only lists and maps support index assignment.")),
+			},
+		}
+	}
+
+	fn write_place(&mut self, place: &Place, value: Option<Value>) -> Result<(), RuntimeError> {
+		match place {
+			Place::Variable(name) => self.environment.borrow_mut().assign(name, value)
+				.map_err(|msg| RuntimeError::new(name.clone(), &msg)),
+			Place::Index { object, index, bracket } => match object {
+				Some(Value::List(items)) => {
+					let len = items.borrow().len();
+					let i = self.check_index(bracket, index, len)?;
+					// Lists store plain values; an uninitialized variable stores nil
+					items.borrow_mut()[i] = value.unwrap_or(Value::Nil);
+					Ok(())
+				}
+				Some(Value::Map(entries)) => match index {
+					Some(Value::Str(key)) => {
+						entries.borrow_mut().insert(key.clone(), value.unwrap_or(Value::Nil));
+						Ok(())
+					}
+					_ => Err(RuntimeError::new(bracket.clone(), "Map keys must be strings.")),
+				},
+				Some(Value::Str(_)) => Err(RuntimeError::new(bracket.clone(), "Strings are immutable.")),
+				_ => Err(RuntimeError::new(bracket.clone(), "Only lists and maps support index assignment.")),
+			},
+		}
+	}
+
+	// Apply a binary operator to already-evaluated operands. Shared by binary
+	// expressions and compound assignment (`a += b` applies `+`).
+	//
+	// Integer operands give an integer result (promoted to a big integer when
+	// it overflows i64) except for `/`, which always divides as floats. If
+	// either operand is a float, both are treated as floats.
+	fn apply_binary(&self, operator: &Token, left_val: &Option<Value>, right_val: &Option<Value>) -> Result<Option<Value>, RuntimeError> {
+		match operator.get_type() {
+			TokenType::Minus => {
+				match self.numeric_operands(operator, left_val, right_val)? {
+					Numeric::Floats(a, b) => Ok(Some(Value::Number(a - b))),
+					ints => Ok(Some(ints.int_op(i64::checked_sub, BigInt::sub))),
+				}
+			}
+			TokenType::Slash => {
+				let (a, b) = self.numeric_operands(operator, left_val, right_val)?.as_floats();
+				Ok(Some(Value::Number(a / b)))
+			}
+			TokenType::Star => {
+				match self.numeric_operands(operator, left_val, right_val)? {
+					Numeric::Floats(a, b) => Ok(Some(Value::Number(a * b))),
+					ints => Ok(Some(ints.int_op(i64::checked_mul, BigInt::mul))),
+				}
+			}
+			TokenType::Percent => {
+				// Remainder takes the sign of the dividend, like C's fmod
+				match self.numeric_operands(operator, left_val, right_val)? {
+					Numeric::Floats(a, b) => Ok(Some(Value::Number(a % b))),
+					ints if ints.divides_by_zero() => Err(RuntimeError::new(operator.clone(), "Division by zero.")),
+					ints => Ok(Some(ints.int_op(i64::checked_rem, |a, b| a.div_rem(b).unwrap().1))),
+				}
+			}
+			TokenType::StarStar => {
+				match self.numeric_operands(operator, left_val, right_val)? {
+					Numeric::Floats(a, b) => Ok(Some(Value::Number(a.powf(b)))),
+					ints => {
+						let (base, exponent) = ints.as_bigs();
+						// A negative exponent gives a fraction, so it falls back to floats
+						if exponent.is_negative() {
+							let (a, b) = ints.as_floats();
+							return Ok(Some(Value::Number(a.powf(b))));
+						}
+						match exponent.to_i64().and_then(|e| u32::try_from(e).ok()) {
+							Some(e) if base.bits().saturating_mul(e as u64) <= MAX_POW_BITS => Ok(Some(Value::from_bigint(base.pow(e)))),
+							_ => Err(RuntimeError::new(operator.clone(), "Integer result is too large.")),
+						}
+					}
+				}
+			}
+			TokenType::Div => {
+				// Floor division rounds toward negative infinity: -7 div 2 == -4
+				match self.numeric_operands(operator, left_val, right_val)? {
+					Numeric::Floats(a, b) => Ok(Some(Value::Number((a / b).floor()))),
+					ints if ints.divides_by_zero() => Err(RuntimeError::new(operator.clone(), "Division by zero.")),
+					ints => {
+						let floor_div = |a: i64, b: i64| a.checked_div(b).map(|q| if a % b != 0 && (a < 0) != (b < 0) { q - 1 } else { q });
+						Ok(Some(ints.int_op(floor_div, |a, b| a.div_floor(b).unwrap())))
+					}
+				}
+			}
+			TokenType::Ampersand | TokenType::Pipe | TokenType::Caret | TokenType::LessLess | TokenType::GreaterGreater => {
+				let a = self.integral_operand(operator, left_val)?;
+				let b = self.integral_operand(operator, right_val)?;
+				let result = match operator.get_type() {
+					TokenType::Ampersand => a & b,
+					TokenType::Pipe => a | b,
+					TokenType::Caret => a ^ b,
+					shift => {
+						if !(0..64).contains(&b) {
+							return Err(RuntimeError::new(operator.clone(), "Shift amount must be between 0 and 63."));
+						}
+						if shift == TokenType::GreaterGreater {
+							a >> b
+						} else if (a << b) >> b == a {
+							a << b
+						} else {
+							// Bits shifted out of the i64 promote, as overflow does for `*`
+							let factor = BigInt::from_i64(2).pow(b as u32);
+							return Ok(Some(Value::from_bigint(BigInt::from_i64(a).mul(&factor))));
+						}
+					}
+				};
+				Ok(Some(Value::Int(result)))
+			}
+			TokenType::Plus => {
+				// Number + Number
+				if let (Some(l), Some(r)) = (left_val, right_val) && l.as_f64().is_some() && r.as_f64().is_some() {
+					return match self.numeric_operands(operator, left_val, right_val)? {
+						Numeric::Floats(a, b) => Ok(Some(Value::Number(a + b))),
+						ints => Ok(Some(ints.int_op(i64::checked_add, BigInt::add))),
+					};
+				}
+
+				// If either operand is a string, convert both to strings and concatenate.
+				if matches!(left_val, Some(Value::Str(_))) || matches!(right_val, Some(Value::Str(_))) {
+					let left_s = self.stringify(left_val);
+					let right_s = self.stringify(right_val);
+					return Ok(Some(Value::Str(format!("{}{}", left_s, right_s))));
+				}
+
+				// Otherwise it's a type error
+				Err(RuntimeError::new(operator.clone(), "Operands must be two numbers or two strings."))
+			}
+			TokenType::Greater | TokenType::GreaterEqual | TokenType::Less | TokenType::LessEqual => {
+				// Any two values compare using the total ordering (see compare.rs);
+				// an uninitialized variable orders as nil
+				let left = left_val.clone().unwrap_or(Value::Nil);
+				let right = right_val.clone().unwrap_or(Value::Nil);
+				let ordering = compare_values(&left, &right);
+				let result = match operator.get_type() {
+					TokenType::Greater => ordering.is_gt(),
+					TokenType::GreaterEqual => ordering.is_ge(),
+					TokenType::Less => ordering.is_lt(),
+					_ => ordering.is_le(),
+				};
+				Ok(Some(Value::Bool(result)))
+			}
+			TokenType::BangEqual => {
+				Ok(Some(Value::Bool(!Interpreter::is_equal(left_val, right_val))))
+			}
+			TokenType::EqualEqual => {
+				Ok(Some(Value::Bool(Interpreter::is_equal(left_val, right_val))))
+			}
+			_ => {
+				// Unsupported operator
+				crate::util::logger::global_logger().log(LogLevel::Error, "interpreter: Unsupported binary operator.");
+				Ok(None)
+			}
 		}
 	}
 
-	fn check_number_operands(&self, operator: &Token, left: &Option<Value>, right: &Option<Value>) -> Result<(), RuntimeError> {
+	// Promote two numeric operands to a common representation
+	fn numeric_operands(&self, operator: &Token, left: &Option<Value>, right: &Option<Value>) -> Result<Numeric, RuntimeError> {
 		match (left, right) {
-			(Some(Value::Number(_)), Some(Value::Number(_))) => Ok(()),
+			(Some(Value::Int(a)), Some(Value::Int(b))) => Ok(Numeric::Ints(*a, *b)),
+			(Some(Value::Int(_) | Value::BigInt(_)), Some(Value::Int(_) | Value::BigInt(_))) => {
+				let as_big = |v: &Value| match v {
+					Value::BigInt(n) => (**n).clone(),
+					Value::Int(n) => BigInt::from_i64(*n),
+					_ => unreachable!(),
+				};
+				Ok(Numeric::Bigs(as_big(left.as_ref().unwrap()), as_big(right.as_ref().unwrap())))
+			}
+			(Some(l), Some(r)) => match (l.as_f64(), r.as_f64()) {
+				(Some(a), Some(b)) => Ok(Numeric::Floats(a, b)),
+				_ => Err(RuntimeError::new(operator.clone(), "Operands must be numbers.")),
+			},
 			_ => Err(RuntimeError::new(operator.clone(), "Operands must be numbers.")),
 		}
 	}
+
+	// Validate a list/string index: it must be an integer in 0..len.
+	fn check_index(&self, bracket: &Token, index: &Option<Value>, len: usize) -> Result<usize, RuntimeError> {
+		match index {
+			Some(Value::Int(n)) => {
+				if *n < 0 || *n as u64 >= len as u64 {
+					return Err(RuntimeError::new(bracket.clone(), &format!("Index {} out of range for length {}.", n, len)));
+				}
+				Ok(*n as usize)
+			}
+			Some(Value::BigInt(n)) => Err(RuntimeError::new(bracket.clone(), &format!("Index {} out of range for length {}.", n, len))),
+			Some(Value::Number(n)) if n.fract() == 0.0 => {
+				if *n < 0.0 || *n >= len as f64 {
+					return Err(RuntimeError::new(bracket.clone(), &format!("Index {} out of range for length {}.", n, len)));
+				}
+				Ok(*n as usize)
+			}
+			_ => Err(RuntimeError::new(bracket.clone(), "Index must be an integer.")),
+		}
+	}
+
+	// Bitwise operators work on numbers with no fractional part that fit in 64 bits
+	fn integral_operand(&self, operator: &Token, operand: &Option<Value>) -> Result<i64, RuntimeError> {
+		match operand {
+			Some(Value::Int(n)) => Ok(*n),
+			Some(Value::Number(n)) if n.fract() == 0.0 && n.abs() < 9.2e18 => Ok(*n as i64),
+			Some(Value::BigInt(_)) => Err(RuntimeError::new(operator.clone(), &format!("Operands of '{}' must fit in 64 bits.", operator.lexeme))),
+			_ => Err(RuntimeError::new(operator.clone(), &format!("Operands of '{}' must be integers.", operator.lexeme))),
+		}
+	}
+
+	fn check_number_operand(&self, operator: &Token, operand: &Option<Value>) -> Result<(), RuntimeError> {
+		match operand {
+			Some(Value::Number(_)) | Some(Value::Int(_)) | Some(Value::BigInt(_)) => Ok(()),
+			_ => Err(RuntimeError::new(operator.clone(), "Operand must be a number.")),
+		}
+	}
 }
 
+// "1 element", "2 elements"
+pub(crate) fn plural(count: usize, noun: &str) -> String {
+	if count == 1 { format!("{} {}", count, noun) } else { format!("{} {}s", count, noun) }
+}
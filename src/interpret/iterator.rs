@@ -0,0 +1,101 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use crate::interpret::generator::GeneratorIterator;
+use crate::interpret::interpreter::{Interpreter, RuntimeError};
+use crate::interpret::value::Value;
+use crate::token::token::Token;
+
+// Iterator protocol used by `for (x in iterable)`. Values are pulled one at a
+// time so sources like ranges never build a list.
+pub trait LoxIterator {
+    // The next value, or None once the iterator is exhausted.
+    fn next(&mut self, interpreter: &mut Interpreter) -> Result<Option<Value>, RuntimeError>;
+}
+
+// A value that can hand out fresh iterators, so it can be looped over more
+// than once. Natives and host code implement this and wrap the value in
+// Value::Iterable to make it usable with for-in.
+pub trait LoxIterable {
+    fn iter(&self) -> Box<dyn LoxIterator>;
+    // Name reported by type()
+    fn type_name(&self) -> &str;
+    fn to_string(&self) -> String;
+}
+
+// Start iterating over a value: lists (elements), maps (sorted keys),
+// strings (characters), generators (yielded values) and any Value::Iterable.
+pub fn iterate(value: &Value, token: &Token) -> Result<Box<dyn LoxIterator>, RuntimeError> {
+    match value {
+        Value::List(items) => Ok(Box::new(ListIterator { items: items.clone(), index: 0 })),
+        Value::Map(entries) => {
+            let keys: Vec<Value> = entries.borrow().keys().map(|k| Value::Str(k.clone())).collect();
+            Ok(Box::new(keys.into_iter()))
+        }
+        Value::Str(s) => {
+            let chars: Vec<Value> = s.chars().map(|c| Value::Str(c.to_string())).collect();
+            Ok(Box::new(chars.into_iter()))
+        }
+        Value::Iterable(iterable) => Ok(iterable.iter()),
+        Value::Generator(generator) => Ok(Box::new(GeneratorIterator { generator: generator.clone(), token: token.clone() })),
+        _ => Err(RuntimeError::new(token.clone(), "Can only iterate over lists, maps, strings, generators and iterables.")),
+    }
+}
+
+// Snapshots (map keys, string characters) iterate over their own copy
+impl LoxIterator for std::vec::IntoIter<Value> {
+    fn next(&mut self, _interpreter: &mut Interpreter) -> Result<Option<Value>, RuntimeError> {
+        Ok(Iterator::next(self))
+    }
+}
+
+// Walks a list by index, so elements appended inside the loop are visited
+// and removing elements ends the loop early instead of failing.
+struct ListIterator {
+    items: Rc<RefCell<Vec<Value>>>,
+    index: usize,
+}
+
+impl LoxIterator for ListIterator {
+    fn next(&mut self, _interpreter: &mut Interpreter) -> Result<Option<Value>, RuntimeError> {
+        let item = self.items.borrow().get(self.index).cloned();
+        self.index += 1;
+        Ok(item)
+    }
+}
+
+// range(start, end, step): integers from start towards end (exclusive).
+pub struct Range {
+    pub start: i64,
+    pub end: i64,
+    pub step: i64,
+}
+
+impl LoxIterable for Range {
+    fn iter(&self) -> Box<dyn LoxIterator> {
+        Box::new(RangeIterator { next: Some(self.start), end: self.end, step: self.step })
+    }
+
+    fn type_name(&self) -> &str { "range" }
+
+    fn to_string(&self) -> String {
+        format!("range({}, {}, {})", self.start, self.end, self.step)
+    }
+}
+
+struct RangeIterator {
+    // None once stepping would overflow
+    next: Option<i64>,
+    end: i64,
+    step: i64,
+}
+
+impl LoxIterator for RangeIterator {
+    fn next(&mut self, _interpreter: &mut Interpreter) -> Result<Option<Value>, RuntimeError> {
+        let current = match self.next {
+            Some(n) if (self.step > 0 && n < self.end) || (self.step < 0 && n > self.end) => n,
+            _ => return Ok(None),
+        };
+        self.next = current.checked_add(self.step);
+        Ok(Some(Value::Int(current)))
+    }
+}
@@ -4,7 +4,9 @@ use crate::interpret::value::Value;
 use crate::interpret::interpreter::RuntimeError;
 use std::rc::Rc;
 use std::cell::RefCell;
-use crate::interpret::callable::LoxCallable;
+use crate::interpret::callable::{Arity, LoxCallable};
+use crate::interpret::generator::{Generator, GeneratorBody};
+use crate::token::token::Token;
 
 #[derive(Debug, Clone)]
 pub struct LoxFunction {
@@ -12,45 +14,95 @@ pub struct LoxFunction {
     // The environment that was active when the function was declared.
     // This is the "closure" that lets the function access surrounding locals.
     pub closure: std::rc::Rc<std::cell::RefCell<crate::interpret::environment::Environment>>,
+    // The compiled body of a `fun*` function, shared by its generators
+    generator_body: Option<GeneratorBody>,
 }
 
 impl LoxFunction {
     pub fn new(declaration: Stmt, closure: std::rc::Rc<std::cell::RefCell<crate::interpret::environment::Environment>>) -> Self {
-        LoxFunction { declaration, closure }
+        let generator_body = match &declaration {
+            Stmt::Function { body, generator: true, .. } => Some(GeneratorBody::compile(body)),
+            _ => None,
+        };
+        LoxFunction { declaration, closure, generator_body }
     }
 
-    pub fn arity(&self) -> usize {
+    // Parameters with defaults are optional; a rest parameter removes the
+    // upper limit.
+    pub fn arity(&self) -> Arity {
         match &self.declaration {
-            Stmt::Function { params, .. } => params.len(),
-            _ => 0,
+            Stmt::Function { params, rest, .. } => {
+                let required = params.iter().filter(|p| p.default.is_none()).count();
+                match rest {
+                    Some(_) => Arity::at_least(required),
+                    None => Arity::range(required, params.len()),
+                }
+            }
+            _ => Arity::exact(0),
         }
     }
 
     pub fn call(&self, interpreter: &mut crate::interpret::interpreter::Interpreter, arguments: &Vec<Value>) -> Result<Option<Value>, RuntimeError> {
+        // Without a call site, report argument errors at the function name
+        let location = match &self.declaration {
+            Stmt::Function { name, .. } => name.clone(),
+            _ => Token::new_token(crate::token::token::TokenType::Nil, "".to_string(), None, 0),
+        };
+        self.call_with_named(interpreter, &location, arguments, &[])
+    }
+
+    // Call with positional and `name: value` arguments. The argument count
+    // is checked against arity() by the caller; `paren` locates errors about
+    // missing or unknown arguments.
+    pub fn call_with_named(&self, interpreter: &mut crate::interpret::interpreter::Interpreter, paren: &Token, arguments: &[Value], named: &[(Token, Value)]) -> Result<Option<Value>, RuntimeError> {
     // Create a new environment for the function execution, enclosing the closure
     // captured when the function was declared.
     let env = Rc::new(RefCell::new(Environment::new_enclosing(self.closure.clone())));
 
         // Bind parameters from the function declaration
-        if let Stmt::Function { params, body, .. } = &self.declaration {
-            for (i, param) in params.iter().enumerate() {
-                let arg = arguments.get(i).cloned().unwrap_or(Value::Nil);
-                env.borrow_mut().define(&param.lexeme, Some(arg));
+        if let Stmt::Function { name, params, rest, body, .. } = &self.declaration {
+            // Positional arguments fill parameters in order, then named ones
+            // fill parameters by name
+            let mut given: Vec<Option<Value>> = params.iter().enumerate().map(|(i, _)| arguments.get(i).cloned()).collect();
+            for (name, value) in named {
+                match params.iter().position(|p| p.name.lexeme == name.lexeme) {
+                    None => return Err(RuntimeError::new(name.clone(), &format!("Unexpected argument '{}'.", name.lexeme))),
+                    Some(i) if given[i].is_some() => return Err(RuntimeError::new(name.clone(), &format!("Argument '{}' was given more than once.", name.lexeme))),
+                    Some(i) => given[i] = Some(value.clone()),
+                }
             }
 
-            // Execute the function body in the new environment, catching return panics
-            use std::panic::{catch_unwind, resume_unwind, take_hook, set_hook};
+            // Defaults are evaluated now, in the new environment, so they can
+            // use the closure and the parameters before them
+            for (param, value) in params.iter().zip(given) {
+                let value = match (value, &param.default) {
+                    (Some(v), _) => v,
+                    (None, Some(default)) => interpreter.evaluate_in(default, env.clone())?.unwrap_or(Value::Nil),
+                    (None, None) => return Err(RuntimeError::new(paren.clone(), &format!("Missing argument '{}'.", param.name.lexeme))),
+                };
+                env.borrow_mut().define(&param.name.lexeme, Some(value));
+            }
 
-            // Temporarily install a no-op panic hook so the unwind doesn't print to stderr
-            let prev_hook = take_hook();
-            set_hook(Box::new(|_info| {}));
+            if let Some(rest) = rest {
+                let extra: Vec<Value> = arguments.iter().skip(params.len()).cloned().collect();
+                env.borrow_mut().define(&rest.lexeme, Some(Value::List(Rc::new(RefCell::new(extra)))));
+            }
+
+            // A generator function doesn't run its body yet; the generator
+            // runs it a step at a time from next()
+            if let Some(compiled) = &self.generator_body {
+                return Ok(Some(Value::Generator(Rc::new(Generator::new(&name.lexeme, compiled, env)))));
+            }
 
+            // Execute the function body in the new environment, catching return panics
+            use std::panic::{catch_unwind, resume_unwind};
+
+            // Silence the panic hook so the unwind doesn't print to stderr
+            let quiet = crate::interpret::return_value::QuietPanics::install();
             let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
                 interpreter.execute_block(body, env)
             }));
-
-            // Restore previous panic hook
-            set_hook(prev_hook);
+            drop(quiet);
 
             match res {
                 Ok(inner_res) => {
@@ -60,7 +112,7 @@ impl LoxFunction {
                 Err(payload) => {
                     // If this was our return marker, extract the stored return value
                     if let Some(s) = payload.downcast_ref::<&str>() {
-                        if *s == "__LOX_RETURN__" {
+                        if *s == crate::interpret::return_value::RETURN_MARKER {
                             let rv = crate::interpret::return_value::take_return();
                             return Ok(rv);
                         }
@@ -77,10 +129,10 @@ impl LoxFunction {
 }
 
 impl LoxCallable for LoxFunction {
-    fn arity(&self) -> usize { self.arity() }
+    fn arity(&self) -> Arity { self.arity() }
 
-    fn call(&self, interpreter: &mut crate::interpret::interpreter::Interpreter, arguments: &Vec<Value>) -> Result<Option<Value>, RuntimeError> {
-        self.call(interpreter, arguments)
+    fn call(&self, interpreter: &mut crate::interpret::interpreter::Interpreter, paren: &crate::token::token::Token, arguments: &Vec<Value>) -> Result<Option<Value>, RuntimeError> {
+        self.call_with_named(interpreter, paren, arguments, &[])
     }
 
     fn to_string(&self) -> String {
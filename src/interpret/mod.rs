@@ -4,3 +4,10 @@ pub mod value;
 pub mod lox_function;
 pub mod callable;
 pub mod return_value;
+pub mod namespace;
+pub mod stdlib;
+pub mod bigint;
+pub mod compare;
+pub mod iterator;
+pub mod generator;
+pub mod module;
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use crate::input::scanner::Scanner;
+use crate::interpret::environment::Environment;
+use crate::interpret::namespace::Namespace;
+use crate::interpret::value::Value;
+use crate::parse::parser::Parser;
+use crate::parse::stmt::Stmt;
+
+// Modules loaded by `import`, keyed by canonical path so the same file
+// reached through different relative paths runs only once.
+#[derive(Default)]
+pub struct ModuleCache {
+    loaded: HashMap<PathBuf, Value>,
+    // Modules whose top level is running, outermost first. Importing one of
+    // these again is a cycle.
+    loading: Vec<PathBuf>,
+}
+
+impl ModuleCache {
+    pub fn new() -> Self {
+        ModuleCache::default()
+    }
+
+    pub fn get(&self, path: &Path) -> Option<Value> {
+        self.loaded.get(path).cloned()
+    }
+
+    // The chain of imports leading back to `path` ("a.lox -> b.lox ->
+    // a.lox") if it is still being loaded.
+    pub fn cycle(&self, path: &Path) -> Option<String> {
+        let start = self.loading.iter().position(|p| p == path)?;
+        let names: Vec<String> = self.loading[start..].iter().chain(std::iter::once(&path.to_path_buf())).map(|p| display_name(p)).collect();
+        Some(names.join(" -> "))
+    }
+
+    pub fn start(&mut self, path: PathBuf) {
+        self.loading.push(path);
+    }
+
+    // Stop tracking the innermost loading module; `module` is its namespace
+    // when it ran without errors.
+    pub fn finish(&mut self, module: Option<Value>) {
+        if let (Some(path), Some(value)) = (self.loading.pop(), module) {
+            self.loaded.insert(path, value);
+        }
+    }
+}
+
+// Resolve an import path against the directory of the importing file (the
+// working directory for the REPL).
+pub fn resolve(importer: Option<&Path>, path: &str) -> Result<PathBuf, String> {
+    let base = importer.and_then(Path::parent).unwrap_or(Path::new(""));
+    let joined = base.join(path);
+    std::fs::canonicalize(&joined).map_err(|e| format!("Can't import '{}': {}.", path, e))
+}
+
+// Scan and parse a whole module up front so a syntax error anywhere stops
+// the import before any of it runs.
+pub fn parse(path: &Path) -> Result<Vec<Stmt>, String> {
+    let scanner = Scanner::new_from_file_quiet(&path.to_string_lossy()).map_err(|e| e.to_string())?;
+    let mut parser = Parser::new(scanner);
+    let mut statements = Vec::new();
+    while !parser.is_at_end() {
+        match parser.parse() {
+            Some(stmt) => statements.push(stmt),
+            None => {
+                if let Some(error) = parser.first_error() {
+                    return Err(error);
+                }
+            }
+        }
+    }
+    Ok(statements)
+}
+
+// Namespace holding a module's top-level bindings, named after its file
+pub fn namespace(path: &Path, env: &Environment) -> Value {
+    let name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let mut ns = Namespace::new(&name);
+    for (member, value) in env.bindings() {
+        // Uninitialized top-level variables are exported as nil
+        ns.define(&member, value.unwrap_or(Value::Nil));
+    }
+    Value::Namespace(std::rc::Rc::new(ns))
+}
+
+fn display_name(path: &Path) -> String {
+    path.file_name().map(|s| s.to_string_lossy().into_owned()).unwrap_or_else(|| path.display().to_string())
+}
@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+use crate::interpret::value::Value;
+
+// A read-only group of named values, such as the `math` standard library
+// module. Members are looked up with property access (`math.PI`).
+#[derive(Debug)]
+pub struct Namespace {
+    pub name: String,
+    members: HashMap<String, Value>,
+}
+
+impl Namespace {
+    pub fn new(name: &str) -> Self {
+        Namespace { name: name.to_string(), members: HashMap::new() }
+    }
+
+    // Add or replace a member. Only used while building the namespace.
+    pub fn define(&mut self, name: &str, value: Value) {
+        self.members.insert(name.to_string(), value);
+    }
+
+    // Look up a member by name.
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.members.get(name).cloned()
+    }
+}
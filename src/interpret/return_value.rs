@@ -1,8 +1,11 @@
 use std::cell::RefCell;
 use crate::interpret::value::Value;
 
+// Panic payload used by `return` to unwind to the enclosing function call.
+pub const RETURN_MARKER: &str = "__LOX_RETURN__";
+
 thread_local! {
-    static RETURN_VALUE: RefCell<Option<Value>> = RefCell::new(None);
+    static RETURN_VALUE: RefCell<Option<Value>> = const { RefCell::new(None) };
 }
 
 pub fn set_return(val: Option<Value>) {
@@ -14,3 +17,28 @@ pub fn set_return(val: Option<Value>) {
 pub fn take_return() -> Option<Value> {
     RETURN_VALUE.with(|c| c.borrow_mut().take())
 }
+
+type PanicHook = Box<dyn Fn(&std::panic::PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+// Silences the panic hook while code that a `return` can unwind out of runs,
+// so the unwind doesn't print a panic message. Dropping it puts the previous
+// hook back, however the guarded code ended.
+pub struct QuietPanics {
+    prev_hook: Option<PanicHook>,
+}
+
+impl QuietPanics {
+    pub fn install() -> Self {
+        let prev_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|_info| {}));
+        QuietPanics { prev_hook: Some(prev_hook) }
+    }
+}
+
+impl Drop for QuietPanics {
+    fn drop(&mut self) {
+        if let Some(hook) = self.prev_hook.take() {
+            std::panic::set_hook(hook);
+        }
+    }
+}
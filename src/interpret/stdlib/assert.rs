@@ -0,0 +1,42 @@
+use std::rc::Rc;
+use crate::interpret::callable::NativeFunction;
+use crate::interpret::environment::Environment;
+use crate::interpret::interpreter::{Interpreter, RuntimeError};
+use crate::interpret::value::Value;
+use crate::token::token::Token;
+
+// Define assert, assert_eq and error in the given (global) environment.
+// Failures are ordinary runtime errors reported at the call's ')' token.
+pub fn register(env: &mut Environment) {
+    env.define("assert", Some(Value::Native(Rc::new(NativeFunction::new_optional("assert", 1, 2, assert)))));
+    env.define("assert_eq", Some(Value::Native(Rc::new(NativeFunction::new("assert_eq", 2, assert_eq)))));
+    env.define("error", Some(Value::Native(Rc::new(NativeFunction::new("error", 1, error)))));
+}
+
+// assert(cond, message?): fail when cond is falsey.
+fn assert(interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    if Interpreter::is_truthy(&Some(arguments[0].clone())) {
+        return Ok(Some(Value::Nil));
+    }
+    let message = match arguments.get(1) {
+        Some(m) => format!("Assertion failed: {}", interpreter.stringify(&Some(m.clone()))),
+        None => "Assertion failed.".to_string(),
+    };
+    Err(RuntimeError::new(paren.clone(), &message))
+}
+
+// assert_eq(a, b): fail when a != b, showing both values.
+fn assert_eq(interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let left = Some(arguments[0].clone());
+    let right = Some(arguments[1].clone());
+    if Interpreter::is_equal(&left, &right) {
+        return Ok(Some(Value::Nil));
+    }
+    let message = format!("assert_eq failed: {} != {}", interpreter.stringify(&left), interpreter.stringify(&right));
+    Err(RuntimeError::new(paren.clone(), &message))
+}
+
+// error(message): always fail with the given message.
+fn error(interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    Err(RuntimeError::new(paren.clone(), &interpreter.stringify(&Some(arguments[0].clone()))))
+}
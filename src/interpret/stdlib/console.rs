@@ -0,0 +1,40 @@
+use std::io::{self, Write};
+use std::rc::Rc;
+use crate::interpret::callable::{NativeFn, NativeFunction};
+use crate::interpret::environment::Environment;
+use crate::interpret::interpreter::{Interpreter, RuntimeError};
+use crate::interpret::value::Value;
+use crate::token::token::Token;
+
+// Define the console input natives in the given (global) environment.
+pub fn register(env: &mut Environment) {
+    let natives: [(&str, usize, NativeFn); 2] = [
+        ("input", 1, input),
+        ("read_line", 0, read_line),
+    ];
+    for (name, arity, function) in natives {
+        env.define(name, Some(Value::Native(Rc::new(NativeFunction::new(name, arity, function)))));
+    }
+}
+
+// Read a line through the interpreter's input source, mapping EOF to nil.
+fn read(interpreter: &mut Interpreter, paren: &Token, name: &str) -> Result<Option<Value>, RuntimeError> {
+    match interpreter.read_input_line() {
+        Ok(Some(line)) => Ok(Some(Value::Str(line))),
+        Ok(None) => Ok(Some(Value::Nil)),
+        Err(e) => Err(RuntimeError::new(paren.clone(), &format!("{}: {}", name, e))),
+    }
+}
+
+// input(prompt): print the prompt (stringified, no newline) and read a line.
+fn input(interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let prompt = interpreter.stringify(&Some(arguments[0].clone()));
+    print!("{}", prompt);
+    io::stdout().flush().ok();
+    read(interpreter, paren, "input")
+}
+
+// read_line(): read a line without printing anything.
+fn read_line(interpreter: &mut Interpreter, paren: &Token, _arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    read(interpreter, paren, "read_line")
+}
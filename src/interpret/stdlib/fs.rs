@@ -0,0 +1,104 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+use crate::interpret::callable::{NativeFn, NativeFunction};
+use crate::interpret::environment::Environment;
+use crate::interpret::interpreter::{Interpreter, RuntimeError};
+use crate::interpret::stdlib::string_arg;
+use crate::interpret::value::Value;
+use crate::token::token::Token;
+
+// Define the file system natives in the given (global) environment. They are
+// always defined but refuse to run unless the interpreter opted in with
+// set_allow_file_system(true).
+pub fn register(env: &mut Environment) {
+    let natives: [(&str, usize, NativeFn); 6] = [
+        ("read_file", 1, read_file),
+        ("write_file", 2, write_file),
+        ("append_file", 2, append_file),
+        ("file_exists", 1, file_exists),
+        ("list_dir", 1, list_dir),
+        ("remove_file", 1, remove_file),
+    ];
+    for (name, arity, function) in natives {
+        env.define(name, Some(Value::Native(Rc::new(NativeFunction::new(name, arity, function)))));
+    }
+}
+
+// Check the opt-in flag and fetch the path argument.
+fn checked_path(interpreter: &Interpreter, name: &str, paren: &Token, arguments: &[Value]) -> Result<String, RuntimeError> {
+    if !interpreter.file_system_allowed() {
+        return Err(RuntimeError::new(paren.clone(), &format!("{}: file system access is disabled.", name)));
+    }
+    string_arg(name, paren, arguments, 0)
+}
+
+// Turn an OS error into a RuntimeError that keeps the OS message.
+fn io_error(name: &str, path: &str, paren: &Token, e: std::io::Error) -> RuntimeError {
+    RuntimeError::new(paren.clone(), &format!("{}: {}: {}", name, path, e))
+}
+
+// read_file(path): whole file as a string.
+fn read_file(interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let path = checked_path(interpreter, "read_file", paren, arguments)?;
+    match fs::read_to_string(&path) {
+        Ok(text) => Ok(Some(Value::Str(text))),
+        Err(e) => Err(io_error("read_file", &path, paren, e)),
+    }
+}
+
+// write_file(path, s): create or truncate the file and write s.
+fn write_file(interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let path = checked_path(interpreter, "write_file", paren, arguments)?;
+    let text = string_arg("write_file", paren, arguments, 1)?;
+    match fs::write(&path, text) {
+        Ok(()) => Ok(Some(Value::Nil)),
+        Err(e) => Err(io_error("write_file", &path, paren, e)),
+    }
+}
+
+// append_file(path, s): append s, creating the file if needed.
+fn append_file(interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let path = checked_path(interpreter, "append_file", paren, arguments)?;
+    let text = string_arg("append_file", paren, arguments, 1)?;
+    let result = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(text.as_bytes()));
+    match result {
+        Ok(()) => Ok(Some(Value::Nil)),
+        Err(e) => Err(io_error("append_file", &path, paren, e)),
+    }
+}
+
+// file_exists(path): true if anything (file or directory) is at path.
+fn file_exists(interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let path = checked_path(interpreter, "file_exists", paren, arguments)?;
+    Ok(Some(Value::Bool(Path::new(&path).exists())))
+}
+
+// list_dir(path): sorted list of entry names in a directory.
+fn list_dir(interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let path = checked_path(interpreter, "list_dir", paren, arguments)?;
+    let entries = fs::read_dir(&path).map_err(|e| io_error("list_dir", &path, paren, e))?;
+    let mut names: Vec<String> = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| io_error("list_dir", &path, paren, e))?;
+        names.push(entry.file_name().to_string_lossy().into_owned());
+    }
+    names.sort();
+    let items: Vec<Value> = names.into_iter().map(Value::Str).collect();
+    Ok(Some(Value::List(Rc::new(RefCell::new(items)))))
+}
+
+// remove_file(path): delete a file.
+fn remove_file(interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let path = checked_path(interpreter, "remove_file", paren, arguments)?;
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(Some(Value::Nil)),
+        Err(e) => Err(io_error("remove_file", &path, paren, e)),
+    }
+}
@@ -0,0 +1,31 @@
+use std::rc::Rc;
+use crate::interpret::callable::NativeFunction;
+use crate::interpret::environment::Environment;
+use crate::interpret::interpreter::{Interpreter, RuntimeError};
+use crate::interpret::iterator::Range;
+use crate::interpret::stdlib::integer_arg;
+use crate::interpret::value::Value;
+use crate::token::token::Token;
+
+// Define the iteration natives in the given (global) environment.
+pub fn register(env: &mut Environment) {
+    env.define("range", Some(Value::Native(Rc::new(NativeFunction::new_optional("range", 1, 3, range)))));
+}
+
+// range(end), range(start, end) or range(start, end, step): the integers from
+// start (default 0) up to but not including end. Values are produced as the
+// loop asks for them rather than stored in a list.
+fn range(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let (start, end) = match arguments.len() {
+        1 => (0, integer_arg("range", paren, arguments, 0)?),
+        _ => (integer_arg("range", paren, arguments, 0)?, integer_arg("range", paren, arguments, 1)?),
+    };
+    let step = match arguments.len() {
+        3 => integer_arg("range", paren, arguments, 2)?,
+        _ => 1,
+    };
+    if step == 0 {
+        return Err(RuntimeError::new(paren.clone(), "range: step must not be zero."));
+    }
+    Ok(Some(Value::Iterable(Rc::new(Range { start, end, step }))))
+}
@@ -0,0 +1,402 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use crate::interpret::bigint::BigInt;
+use crate::interpret::callable::NativeFunction;
+use crate::interpret::environment::Environment;
+use crate::interpret::interpreter::{Interpreter, RuntimeError};
+use crate::interpret::stdlib::{index_arg, string_arg};
+use crate::interpret::value::Value;
+use crate::token::token::Token;
+
+// Define json_parse and json_stringify in the given (global) environment.
+pub fn register(env: &mut Environment) {
+    env.define("json_parse", Some(Value::Native(Rc::new(NativeFunction::new("json_parse", 1, json_parse)))));
+    env.define("json_stringify", Some(Value::Native(Rc::new(NativeFunction::new_optional("json_stringify", 1, 2, json_stringify)))));
+}
+
+// json_parse(s): JSON text to Lox values. Arrays become lists and objects
+// become maps. Errors report the byte offset where parsing failed.
+fn json_parse(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let text = string_arg("json_parse", paren, arguments, 0)?;
+    match parse(&text) {
+        Ok(value) => Ok(Some(value)),
+        Err((message, offset)) => Err(RuntimeError::new(paren.clone(), &format!("json_parse: {} at byte {}.", message, offset))),
+    }
+}
+
+// json_stringify(v, indent?): Lox value to JSON text, pretty-printed with
+// `indent` spaces per level when given.
+fn json_stringify(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let indent = if arguments.len() > 1 { index_arg("json_stringify", paren, arguments, 1)? } else { 0 };
+    let mut out = String::new();
+    let mut visiting: Vec<*const ()> = Vec::new();
+    match write_value(&arguments[0], indent, 0, &mut out, &mut visiting) {
+        Ok(()) => Ok(Some(Value::Str(out))),
+        Err(message) => Err(RuntimeError::new(paren.clone(), &format!("json_stringify: {}", message))),
+    }
+}
+
+// Deepest nesting of arrays and objects that parse and write_value accept.
+// Both recurse once per level, so this keeps deep input from overflowing
+// the stack.
+const MAX_DEPTH: usize = 512;
+
+// Parse a complete JSON document; trailing non-whitespace is an error.
+pub fn parse(text: &str) -> Result<Value, (String, usize)> {
+    let mut parser = JsonParser { text, bytes: text.as_bytes(), pos: 0, depth: 0 };
+    parser.skip_whitespace();
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.bytes.len() {
+        return Err(parser.unexpected());
+    }
+    Ok(value)
+}
+
+struct JsonParser<'a> {
+    text: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    // Arrays and objects currently open
+    depth: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    // Error for whatever is at the current position.
+    fn unexpected(&self) -> (String, usize) {
+        match self.text[self.pos..].chars().next() {
+            Some(c) => (format!("Unexpected character '{}'", c), self.pos),
+            None => ("Unexpected end of input".to_string(), self.pos),
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), (String, usize)> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, (String, usize)> {
+        match self.peek() {
+            Some(b'{' | b'[') => {
+                if self.depth == MAX_DEPTH {
+                    return Err(("JSON nested too deeply".to_string(), self.pos));
+                }
+                self.depth += 1;
+                let value = if self.peek() == Some(b'{') { self.parse_object() } else { self.parse_array() };
+                self.depth -= 1;
+                value
+            }
+            Some(b'"') => Ok(Value::Str(self.parse_string()?)),
+            Some(b't') => self.parse_keyword("true", Value::Bool(true)),
+            Some(b'f') => self.parse_keyword("false", Value::Bool(false)),
+            Some(b'n') => self.parse_keyword("null", Value::Nil),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn parse_keyword(&mut self, word: &str, value: Value) -> Result<Value, (String, usize)> {
+        if self.text[self.pos..].starts_with(word) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Value, (String, usize)> {
+        self.expect(b'{')?;
+        let mut entries: BTreeMap<String, Value> = BTreeMap::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Value::Map(Rc::new(RefCell::new(entries))));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.unexpected());
+            }
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            self.skip_whitespace();
+            let value = self.parse_value()?;
+            entries.insert(key, value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Value::Map(Rc::new(RefCell::new(entries))));
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Value, (String, usize)> {
+        self.expect(b'[')?;
+        let mut items: Vec<Value> = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Value::List(Rc::new(RefCell::new(items))));
+        }
+        loop {
+            self.skip_whitespace();
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Value::List(Rc::new(RefCell::new(items))));
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, (String, usize)> {
+        self.expect(b'"')?;
+        let mut out = String::new();
+        loop {
+            // Copy the run of plain characters up to the next quote/escape.
+            let start = self.pos;
+            while let Some(b) = self.peek() {
+                if b == b'"' || b == b'\\' || b < 0x20 {
+                    break;
+                }
+                self.pos += 1;
+            }
+            out.push_str(&self.text[start..self.pos]);
+
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let escape_pos = self.pos;
+                    let c = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            self.pos += 1;
+                            out.push(self.parse_unicode_escape()?);
+                            continue;
+                        }
+                        _ => return Err(("Invalid escape sequence".to_string(), escape_pos)),
+                    };
+                    self.pos += 1;
+                    out.push(c);
+                }
+                Some(_) => return Err(("Control character in string".to_string(), self.pos)),
+                None => return Err(("Unterminated string".to_string(), self.pos)),
+            }
+        }
+    }
+
+    // Parse the XXXX of a \uXXXX escape (after the 'u'), combining
+    // surrogate pairs into one char.
+    fn parse_unicode_escape(&mut self) -> Result<char, (String, usize)> {
+        let start = self.pos;
+        let high = self.parse_hex4()?;
+        if (0xD800..0xDC00).contains(&high) {
+            if !self.text[self.pos..].starts_with("\\u") {
+                return Err(("Unpaired surrogate in \\u escape".to_string(), start));
+            }
+            self.pos += 2;
+            let low = self.parse_hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(("Unpaired surrogate in \\u escape".to_string(), start));
+            }
+            let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+            return char::from_u32(code).ok_or(("Invalid \\u escape".to_string(), start));
+        }
+        char::from_u32(high).ok_or(("Unpaired surrogate in \\u escape".to_string(), start))
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, (String, usize)> {
+        let digits = self.text.get(self.pos..self.pos + 4).filter(|d| d.bytes().all(|b| b.is_ascii_hexdigit()));
+        match digits {
+            Some(d) => {
+                self.pos += 4;
+                Ok(u32::from_str_radix(d, 16).unwrap_or(0))
+            }
+            None => Err(("Invalid \\u escape".to_string(), self.pos)),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value, (String, usize)> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        // Integer part: a single 0 or a non-zero digit followed by digits
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.skip_digits(),
+            _ => return Err(self.unexpected()),
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.unexpected());
+            }
+            self.skip_digits();
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.unexpected());
+            }
+            self.skip_digits();
+        }
+        // Numbers without a fraction or exponent are integers when they fit
+        let text = &self.text[start..self.pos];
+        if let Some(n) = BigInt::parse(text) {
+            return Ok(Value::from_bigint(n));
+        }
+        match text.parse::<f64>() {
+            Ok(n) => Ok(Value::Number(n)),
+            Err(_) => Err(("Invalid number".to_string(), start)),
+        }
+    }
+
+    fn skip_digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+    }
+}
+
+// Append the JSON form of `value` to `out`. `visiting` holds the lists and
+// maps currently being written so cycles are reported instead of looping.
+fn write_value(value: &Value, indent: usize, depth: usize, out: &mut String, visiting: &mut Vec<*const ()>) -> Result<(), String> {
+    if depth == MAX_DEPTH && matches!(value, Value::List(_) | Value::Map(_)) {
+        return Err("JSON nested too deeply.".to_string());
+    }
+    match value {
+        Value::Nil => out.push_str("null"),
+        Value::Bool(b) => out.push_str(&b.to_string()),
+        Value::Number(n) => {
+            if !n.is_finite() {
+                return Err(format!("cannot serialize the number {}.", n));
+            }
+            let mut text = format!("{}", n);
+            if text.ends_with(".0") {
+                text.truncate(text.len() - 2);
+            }
+            out.push_str(&text);
+        }
+        Value::Int(n) => out.push_str(&n.to_string()),
+        Value::BigInt(n) => out.push_str(&n.to_string()),
+        Value::Str(s) => write_string(s, out),
+        Value::List(items) => {
+            let ptr = Rc::as_ptr(items) as *const ();
+            if visiting.contains(&ptr) {
+                return Err("cannot serialize a cyclic structure.".to_string());
+            }
+            visiting.push(ptr);
+            let items = items.borrow();
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(indent, depth + 1, out);
+                write_value(item, indent, depth + 1, out, visiting)?;
+            }
+            if !items.is_empty() {
+                newline(indent, depth, out);
+            }
+            out.push(']');
+            visiting.pop();
+        }
+        Value::Map(entries) => {
+            let ptr = Rc::as_ptr(entries) as *const ();
+            if visiting.contains(&ptr) {
+                return Err("cannot serialize a cyclic structure.".to_string());
+            }
+            visiting.push(ptr);
+            let entries = entries.borrow();
+            out.push('{');
+            for (i, (key, item)) in entries.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(indent, depth + 1, out);
+                write_string(key, out);
+                out.push(':');
+                if indent > 0 {
+                    out.push(' ');
+                }
+                write_value(item, indent, depth + 1, out, visiting)?;
+            }
+            if !entries.is_empty() {
+                newline(indent, depth, out);
+            }
+            out.push('}');
+            visiting.pop();
+        }
+        Value::Function(_) | Value::Native(_) => return Err("cannot serialize a function.".to_string()),
+        Value::Namespace(ns) => return Err(format!("cannot serialize namespace '{}'.", ns.name)),
+        Value::Error(_) => return Err("cannot serialize an error value.".to_string()),
+        Value::Iterable(it) => return Err(format!("cannot serialize a {}.", it.type_name())),
+        Value::Generator(_) => return Err("cannot serialize a generator.".to_string()),
+    }
+    Ok(())
+}
+
+// In pretty mode start a new line indented to `depth`; compact mode adds nothing.
+fn newline(indent: usize, depth: usize, out: &mut String) {
+    if indent > 0 {
+        out.push('\n');
+        out.push_str(&" ".repeat(indent * depth));
+    }
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
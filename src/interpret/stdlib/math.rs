@@ -0,0 +1,106 @@
+use std::rc::Rc;
+use crate::interpret::callable::{NativeFn, NativeFunction};
+use crate::interpret::interpreter::{Interpreter, RuntimeError};
+use crate::interpret::namespace::Namespace;
+use crate::interpret::stdlib::number_arg;
+use crate::interpret::value::Value;
+use crate::token::token::Token;
+
+// Build the `math` namespace: numeric natives plus the PI and E constants.
+pub fn namespace() -> Value {
+    let mut ns = Namespace::new("math");
+    ns.define("PI", Value::Number(std::f64::consts::PI));
+    ns.define("E", Value::Number(std::f64::consts::E));
+
+    let natives: [(&str, usize, NativeFn); 14] = [
+        ("sqrt", 1, sqrt),
+        ("pow", 2, pow),
+        ("abs", 1, abs),
+        ("floor", 1, floor),
+        ("ceil", 1, ceil),
+        ("round", 1, round),
+        ("min", 2, min),
+        ("max", 2, max),
+        ("sin", 1, sin),
+        ("cos", 1, cos),
+        ("tan", 1, tan),
+        ("atan2", 2, atan2),
+        ("log", 1, log),
+        ("exp", 1, exp),
+    ];
+    for (name, arity, function) in natives {
+        let full_name = format!("math.{}", name);
+        ns.define(name, Value::Native(Rc::new(NativeFunction::new(&full_name, arity, function))));
+    }
+
+    Value::Namespace(Rc::new(ns))
+}
+
+// Apply a one-argument f64 function to argument 1.
+fn unary(name: &str, paren: &Token, arguments: &[Value], op: fn(f64) -> f64) -> Result<Option<Value>, RuntimeError> {
+    let x = number_arg(name, paren, arguments, 0)?;
+    Ok(Some(Value::Number(op(x))))
+}
+
+// Apply a two-argument f64 function to arguments 1 and 2.
+fn binary(name: &str, paren: &Token, arguments: &[Value], op: fn(f64, f64) -> f64) -> Result<Option<Value>, RuntimeError> {
+    let a = number_arg(name, paren, arguments, 0)?;
+    let b = number_arg(name, paren, arguments, 1)?;
+    Ok(Some(Value::Number(op(a, b))))
+}
+
+fn sqrt(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    unary("math.sqrt", paren, arguments, f64::sqrt)
+}
+
+fn pow(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    binary("math.pow", paren, arguments, f64::powf)
+}
+
+fn abs(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    unary("math.abs", paren, arguments, f64::abs)
+}
+
+fn floor(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    unary("math.floor", paren, arguments, f64::floor)
+}
+
+fn ceil(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    unary("math.ceil", paren, arguments, f64::ceil)
+}
+
+fn round(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    unary("math.round", paren, arguments, f64::round)
+}
+
+fn min(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    binary("math.min", paren, arguments, f64::min)
+}
+
+fn max(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    binary("math.max", paren, arguments, f64::max)
+}
+
+fn sin(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    unary("math.sin", paren, arguments, f64::sin)
+}
+
+fn cos(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    unary("math.cos", paren, arguments, f64::cos)
+}
+
+fn tan(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    unary("math.tan", paren, arguments, f64::tan)
+}
+
+fn atan2(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    binary("math.atan2", paren, arguments, f64::atan2)
+}
+
+fn log(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    unary("math.log", paren, arguments, f64::ln)
+}
+
+fn exp(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    unary("math.exp", paren, arguments, f64::exp)
+}
@@ -0,0 +1,62 @@
+// Standard library modules built from NativeFunction values. Each module
+// exposes a constructor that returns the value to bind in the globals.
+pub mod math;
+pub mod string;
+pub mod console;
+pub mod fs;
+pub mod random;
+pub mod json;
+pub mod assert;
+pub mod order;
+pub mod iter;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+use crate::interpret::interpreter::RuntimeError;
+use crate::interpret::value::Value;
+use crate::token::token::Token;
+
+// Fetch argument `index` as a number, or report which argument was wrong.
+pub fn number_arg(name: &str, paren: &Token, arguments: &[Value], index: usize) -> Result<f64, RuntimeError> {
+    match arguments.get(index).and_then(|v| v.as_f64()) {
+        Some(n) => Ok(n),
+        None => Err(RuntimeError::new(paren.clone(), &format!("{}: argument {} must be a number.", name, index + 1))),
+    }
+}
+
+// Fetch argument `index` as an integral number.
+pub fn integer_arg(name: &str, paren: &Token, arguments: &[Value], index: usize) -> Result<i64, RuntimeError> {
+    if let Some(Value::Int(n)) = arguments.get(index) {
+        return Ok(*n);
+    }
+    let n = number_arg(name, paren, arguments, index)?;
+    if n.fract() != 0.0 || n.abs() > 9007199254740992.0 {
+        return Err(RuntimeError::new(paren.clone(), &format!("{}: argument {} must be an integer.", name, index + 1)));
+    }
+    Ok(n as i64)
+}
+
+// Fetch argument `index` as a string.
+pub fn string_arg(name: &str, paren: &Token, arguments: &[Value], index: usize) -> Result<String, RuntimeError> {
+    match arguments.get(index) {
+        Some(Value::Str(s)) => Ok(s.clone()),
+        _ => Err(RuntimeError::new(paren.clone(), &format!("{}: argument {} must be a string.", name, index + 1))),
+    }
+}
+
+// Fetch argument `index` as a non-negative integer (for positions/counts).
+pub fn index_arg(name: &str, paren: &Token, arguments: &[Value], index: usize) -> Result<usize, RuntimeError> {
+    match arguments.get(index) {
+        Some(Value::Int(n)) if *n >= 0 => Ok(*n as usize),
+        Some(Value::Number(n)) if n.fract() == 0.0 && *n >= 0.0 => Ok(*n as usize),
+        _ => Err(RuntimeError::new(paren.clone(), &format!("{}: argument {} must be a non-negative integer.", name, index + 1))),
+    }
+}
+
+// Fetch argument `index` as a list.
+pub fn list_arg(name: &str, paren: &Token, arguments: &[Value], index: usize) -> Result<Rc<RefCell<Vec<Value>>>, RuntimeError> {
+    match arguments.get(index) {
+        Some(Value::List(items)) => Ok(items.clone()),
+        _ => Err(RuntimeError::new(paren.clone(), &format!("{}: argument {} must be a list.", name, index + 1))),
+    }
+}
@@ -0,0 +1,37 @@
+use std::cmp::Ordering;
+use std::rc::Rc;
+use crate::interpret::callable::NativeFunction;
+use crate::interpret::compare::compare_values;
+use crate::interpret::environment::Environment;
+use crate::interpret::interpreter::{Interpreter, RuntimeError};
+use crate::interpret::stdlib::list_arg;
+use crate::interpret::value::Value;
+use crate::token::token::Token;
+
+// Define compare and sort in the given (global) environment. Both use the
+// same total ordering as the comparison operators.
+pub fn register(env: &mut Environment) {
+    env.define("compare", Some(Value::Native(Rc::new(NativeFunction::new("compare", 2, compare)))));
+    env.define("sort", Some(Value::Native(Rc::new(NativeFunction::new("sort", 1, sort)))));
+}
+
+// compare(a, b): -1, 0 or 1 as a orders before, equal to or after b.
+fn compare(_interpreter: &mut Interpreter, _paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let result = match compare_values(&arguments[0], &arguments[1]) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    };
+    Ok(Some(Value::Int(result)))
+}
+
+// sort(list): sort the list in place (stable), ascending.
+fn sort(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let items = list_arg("sort", paren, arguments, 0)?;
+    // Take the elements out while sorting so a list that contains itself
+    // isn't borrowed twice
+    let mut elements = std::mem::take(&mut *items.borrow_mut());
+    elements.sort_by(compare_values);
+    *items.borrow_mut() = elements;
+    Ok(Some(Value::Nil))
+}
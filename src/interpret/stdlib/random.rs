@@ -0,0 +1,94 @@
+use std::rc::Rc;
+use crate::interpret::callable::{NativeFn, NativeFunction};
+use crate::interpret::environment::Environment;
+use crate::interpret::interpreter::{Interpreter, RuntimeError};
+use crate::interpret::stdlib::{list_arg, integer_arg};
+use crate::interpret::value::Value;
+use crate::token::token::Token;
+
+// Small deterministic generator (SplitMix64). Good enough for scripts and,
+// unlike OS entropy, reproducible after seed(n).
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    // Seed from the clock so unseeded scripts still vary between runs.
+    pub fn from_time() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64;
+        Rng::new(nanos)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform float in [0, 1) built from the top 53 bits.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Uniform integer in [0, bound) using a widening multiply.
+    pub fn next_below(&mut self, bound: u64) -> u64 {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+}
+
+// Define the random natives in the given (global) environment.
+pub fn register(env: &mut Environment) {
+    let natives: [(&str, usize, NativeFn); 4] = [
+        ("random", 0, random),
+        ("random_int", 2, random_int),
+        ("shuffle", 1, shuffle),
+        ("seed", 1, seed),
+    ];
+    for (name, arity, function) in natives {
+        env.define(name, Some(Value::Native(Rc::new(NativeFunction::new(name, arity, function)))));
+    }
+}
+
+// random(): float in [0, 1).
+fn random(interpreter: &mut Interpreter, _paren: &Token, _arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    Ok(Some(Value::Number(interpreter.rng().next_f64())))
+}
+
+// random_int(lo, hi): integer in [lo, hi], both ends included.
+fn random_int(interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let lo = integer_arg("random_int", paren, arguments, 0)?;
+    let hi = integer_arg("random_int", paren, arguments, 1)?;
+    if lo > hi {
+        return Err(RuntimeError::new(paren.clone(), &format!("random_int: lower bound {} is greater than upper bound {}.", lo, hi)));
+    }
+    let span = (hi - lo) as u64 + 1;
+    let offset = interpreter.rng().next_below(span);
+    Ok(Some(Value::Int(lo + offset as i64)))
+}
+
+// shuffle(list): shuffle the list in place (Fisher-Yates).
+fn shuffle(interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let items = list_arg("shuffle", paren, arguments, 0)?;
+    let mut items = items.borrow_mut();
+    for i in (1..items.len()).rev() {
+        let j = interpreter.rng().next_below(i as u64 + 1) as usize;
+        items.swap(i, j);
+    }
+    Ok(Some(Value::Nil))
+}
+
+// seed(n): restart the generator so later results are reproducible.
+fn seed(interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let n = integer_arg("seed", paren, arguments, 0)?;
+    *interpreter.rng() = Rng::new(n as u64);
+    Ok(Some(Value::Nil))
+}
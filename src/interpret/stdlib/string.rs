@@ -0,0 +1,150 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use crate::interpret::callable::{NativeFn, NativeFunction};
+use crate::interpret::environment::Environment;
+use crate::interpret::interpreter::{Interpreter, RuntimeError};
+use crate::interpret::stdlib::{index_arg, list_arg, number_arg, string_arg};
+use crate::interpret::value::Value;
+use crate::token::token::Token;
+
+// Define the string natives in the given (global) environment. All
+// positions and lengths count chars, not bytes, so non-ASCII text behaves.
+pub fn register(env: &mut Environment) {
+    let natives: [(&str, usize, NativeFn); 14] = [
+        ("len", 1, len),
+        ("substr", 3, substr),
+        ("index_of", 2, index_of),
+        ("contains", 2, contains),
+        ("starts_with", 2, starts_with),
+        ("ends_with", 2, ends_with),
+        ("upper", 1, upper),
+        ("lower", 1, lower),
+        ("trim", 1, trim),
+        ("replace", 3, replace),
+        ("split", 2, split),
+        ("join", 2, join),
+        ("chr", 1, chr),
+        ("ord", 1, ord),
+    ];
+    for (name, arity, function) in natives {
+        env.define(name, Some(Value::Native(Rc::new(NativeFunction::new(name, arity, function)))));
+    }
+}
+
+fn str_value(s: String) -> Result<Option<Value>, RuntimeError> {
+    Ok(Some(Value::Str(s)))
+}
+
+// len(x): number of chars in a string or entries in a list or map.
+fn len(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    match &arguments[0] {
+        Value::Str(s) => Ok(Some(Value::Int(s.chars().count() as i64))),
+        Value::List(items) => Ok(Some(Value::Int(items.borrow().len() as i64))),
+        Value::Map(entries) => Ok(Some(Value::Int(entries.borrow().len() as i64))),
+        _ => Err(RuntimeError::new(paren.clone(), "len: argument 1 must be a string, list or map.")),
+    }
+}
+
+// substr(s, start, end): chars in [start, end).
+fn substr(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let s = string_arg("substr", paren, arguments, 0)?;
+    let start = index_arg("substr", paren, arguments, 1)?;
+    let end = index_arg("substr", paren, arguments, 2)?;
+    let count = s.chars().count();
+    if start > end || end > count {
+        return Err(RuntimeError::new(paren.clone(), &format!("substr: range {}..{} out of bounds for length {}.", start, end, count)));
+    }
+    str_value(s.chars().skip(start).take(end - start).collect())
+}
+
+// index_of(s, sub): char position of the first match, or -1.
+fn index_of(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let s = string_arg("index_of", paren, arguments, 0)?;
+    let sub = string_arg("index_of", paren, arguments, 1)?;
+    let position = match s.find(&sub) {
+        Some(byte_pos) => s[..byte_pos].chars().count() as i64,
+        None => -1,
+    };
+    Ok(Some(Value::Int(position)))
+}
+
+fn contains(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let s = string_arg("contains", paren, arguments, 0)?;
+    let sub = string_arg("contains", paren, arguments, 1)?;
+    Ok(Some(Value::Bool(s.contains(&sub))))
+}
+
+fn starts_with(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let s = string_arg("starts_with", paren, arguments, 0)?;
+    let prefix = string_arg("starts_with", paren, arguments, 1)?;
+    Ok(Some(Value::Bool(s.starts_with(&prefix))))
+}
+
+fn ends_with(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let s = string_arg("ends_with", paren, arguments, 0)?;
+    let suffix = string_arg("ends_with", paren, arguments, 1)?;
+    Ok(Some(Value::Bool(s.ends_with(&suffix))))
+}
+
+fn upper(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    str_value(string_arg("upper", paren, arguments, 0)?.to_uppercase())
+}
+
+fn lower(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    str_value(string_arg("lower", paren, arguments, 0)?.to_lowercase())
+}
+
+fn trim(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    str_value(string_arg("trim", paren, arguments, 0)?.trim().to_string())
+}
+
+// replace(s, from, to): replace every occurrence of `from`.
+fn replace(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let s = string_arg("replace", paren, arguments, 0)?;
+    let from = string_arg("replace", paren, arguments, 1)?;
+    let to = string_arg("replace", paren, arguments, 2)?;
+    if from.is_empty() {
+        return Err(RuntimeError::new(paren.clone(), "replace: argument 2 must not be empty."));
+    }
+    str_value(s.replace(&from, &to))
+}
+
+// split(s, sep): list of pieces. An empty separator splits into chars.
+fn split(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let s = string_arg("split", paren, arguments, 0)?;
+    let sep = string_arg("split", paren, arguments, 1)?;
+    let parts: Vec<Value> = if sep.is_empty() {
+        s.chars().map(|c| Value::Str(c.to_string())).collect()
+    } else {
+        s.split(sep.as_str()).map(|p| Value::Str(p.to_string())).collect()
+    };
+    Ok(Some(Value::List(Rc::new(RefCell::new(parts)))))
+}
+
+// join(list, sep): concatenate the stringified elements with `sep` between.
+fn join(interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let items = list_arg("join", paren, arguments, 0)?;
+    let sep = string_arg("join", paren, arguments, 1)?;
+    let parts: Vec<String> = items.borrow().iter().map(|v| interpreter.stringify(&Some(v.clone()))).collect();
+    str_value(parts.join(&sep))
+}
+
+// chr(n): one-char string for a Unicode code point.
+fn chr(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let n = number_arg("chr", paren, arguments, 0)?;
+    let c = if n.fract() == 0.0 && n >= 0.0 && n <= u32::MAX as f64 { char::from_u32(n as u32) } else { None };
+    match c {
+        Some(c) => str_value(c.to_string()),
+        None => Err(RuntimeError::new(paren.clone(), &format!("chr: {} is not a valid code point.", n))),
+    }
+}
+
+// ord(s): code point of a one-char string.
+fn ord(_interpreter: &mut Interpreter, paren: &Token, arguments: &[Value]) -> Result<Option<Value>, RuntimeError> {
+    let s = string_arg("ord", paren, arguments, 0)?;
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(Some(Value::Int(c as u32 as i64))),
+        _ => Err(RuntimeError::new(paren.clone(), "ord: argument 1 must be a single character.")),
+    }
+}
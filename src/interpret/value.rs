@@ -1,18 +1,46 @@
 use std::rc::Rc;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::fmt;
 use crate::interpret::callable::LoxCallable;
 use crate::parse::stmt::Stmt;
+use crate::interpret::bigint::BigInt;
 
 #[derive(Clone)]
 pub enum Value {
     Nil,
     Number(f64),
+    // Integer literals and integer arithmetic; mixes with Number as a float
+    Int(i64),
+    // Integers outside the i64 range; integer arithmetic promotes to this on
+    // overflow and results that fit again become Int
+    BigInt(Rc<BigInt>),
     Str(String),
     Bool(bool),
     // User-defined function
     Function(Rc<crate::interpret::lox_function::LoxFunction>),
     // Native or other callable implemented in Rust
     Native(Rc<dyn LoxCallable>),
+    // Named collection of values reached with '.', e.g. `math.sqrt`
+    Namespace(Rc<crate::interpret::namespace::Namespace>),
+    // Mutable list shared by reference, e.g. `[1, 2, 3]`
+    List(Rc<RefCell<Vec<Value>>>),
+    // String-keyed map shared by reference. Keys are kept sorted so output
+    // (printing, JSON) is deterministic.
+    Map(Rc<RefCell<BTreeMap<String, Value>>>),
+    // A runtime error caught by `catch`, with `.message` and `.line`
+    Error(Rc<ErrorValue>),
+    // Something for-in can loop over that isn't a list, map or string, e.g.
+    // range(); see interpret::iterator
+    Iterable(Rc<dyn crate::interpret::iterator::LoxIterable>),
+    // Suspended `fun*` call, resumed with `.next()`; see interpret::generator
+    Generator(Rc<crate::interpret::generator::Generator>),
+}
+
+#[derive(Debug)]
+pub struct ErrorValue {
+    pub message: String,
+    pub line: usize,
 }
 
 impl fmt::Debug for Value {
@@ -20,6 +48,8 @@ impl fmt::Debug for Value {
         match self {
             Value::Nil => write!(f, "Nil"),
             Value::Number(n) => write!(f, "Number({})", n),
+            Value::Int(n) => write!(f, "Int({})", n),
+            Value::BigInt(n) => write!(f, "BigInt({})", n),
             Value::Str(s) => write!(f, "Str({})", s),
             Value::Bool(b) => write!(f, "Bool({})", b),
             Value::Function(func) => match &func.declaration {
@@ -27,6 +57,51 @@ impl fmt::Debug for Value {
                 _ => write!(f, "Function(<fn>)"),
             },
             Value::Native(_) => write!(f, "Native(<native fn>)"),
+            Value::Namespace(ns) => write!(f, "Namespace({})", ns.name),
+            Value::List(items) => write!(f, "List({:?})", items.borrow()),
+            Value::Map(entries) => write!(f, "Map({:?})", entries.borrow()),
+            Value::Error(e) => write!(f, "Error({})", e.message),
+            Value::Iterable(it) => write!(f, "Iterable({})", it.to_string()),
+            Value::Generator(g) => write!(f, "Generator({})", g.name),
+        }
+    }
+}
+
+impl Value {
+    // Name of the value's runtime type, as reported by type()
+    pub fn type_name(&self) -> &str {
+        match self {
+            Value::Nil => "nil",
+            Value::Number(_) | Value::Int(_) | Value::BigInt(_) => "number",
+            Value::Str(_) => "string",
+            Value::Bool(_) => "bool",
+            Value::Function(_) => "function",
+            Value::Native(_) => "native",
+            Value::Namespace(_) => "namespace",
+            Value::List(_) => "list",
+            Value::Map(_) => "map",
+            Value::Error(_) => "error",
+            Value::Iterable(it) => it.type_name(),
+            Value::Generator(_) => "generator",
+        }
+    }
+
+    // Numeric value as a float, for operations that don't distinguish
+    // integers (math natives, mixed arithmetic).
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            Value::Int(n) => Some(*n as f64),
+            Value::BigInt(n) => Some(n.to_f64()),
+            _ => None,
+        }
+    }
+
+    // Wrap an integer result, using Int whenever it fits
+    pub fn from_bigint(n: BigInt) -> Value {
+        match n.to_i64() {
+            Some(small) => Value::Int(small),
+            None => Value::BigInt(Rc::new(n)),
         }
     }
 }
@@ -11,12 +11,17 @@ fn main() {
     global_logger().log(LogLevel::Info, "main: Initializing configuration settings...");
 
     // Choose a scanner based on whether a filename was provided.
-    let args: Vec<String> = std::env::args().collect();
+    // `--allow-fs` opts in to the file system natives (read_file, ...).
+    // `--strict` makes reading an uninitialized variable a runtime error.
+    let args: Vec<String> = std::env::args().skip(1).collect();
     let mut interpreter = Interpreter::new();
+    interpreter.set_allow_file_system(args.iter().any(|a| a == "--allow-fs"));
+    interpreter.set_strict(args.iter().any(|a| a == "--strict"));
+    let files: Vec<&String> = args.iter().filter(|a| !a.starts_with("--")).collect();
 
-    let scanner = if args.len() > 1 {
-        let input_path = &args[1];
+    let scanner = if let Some(input_path) = files.first() {
     global_logger().log(LogLevel::Info, format!("main: Input file provided: {}", input_path));
+        interpreter.set_script_path(input_path);
         match Scanner::new_from_file(input_path) {
             Ok(s) => s,
             Err(e) => {
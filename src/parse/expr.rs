@@ -1,4 +1,6 @@
 use crate::token::token::Token; // Assuming your Token is defined in a 'token' module
+use crate::interpret::bigint::BigInt;
+use crate::parse::pattern::Pattern;
 
 // --- AST NODE STRUCTS ---
 
@@ -28,6 +30,10 @@ pub struct LiteralExpr {
 #[derive(Debug, Clone, PartialEq)]
 pub enum LiteralValue {
     Number(f64),
+    // Literals written without a fraction or exponent
+    Int(i64),
+    // Integer literals too large for i64
+    BigInt(BigInt),
     Str(String),
     Bool(bool),
 }
@@ -56,6 +62,67 @@ pub struct CallExpr {
     pub callee: Box<Expr>,
     pub paren: Token,
     pub arguments: Vec<Expr>,
+    // Named arguments `name: value`, after the positional ones
+    pub named: Vec<(Token, Expr)>,
+    // `callee?.(...)`: gives nil without evaluating the arguments when the
+    // callee is nil
+    pub optional: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetExpr {
+    pub object: Box<Expr>,
+    pub name: Token,
+    // `object?.name`: gives nil when the object is nil
+    pub optional: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ListExpr {
+    // The '[' token, for errors about the list as an assignment target
+    pub bracket: Token,
+    pub elements: Vec<Expr>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IndexExpr {
+    pub object: Box<Expr>,
+    // The closing ']' token, used to report index errors.
+    pub bracket: Token,
+    pub index: Box<Expr>,
+}
+
+// `object[index] = value`
+#[derive(Debug, Clone)]
+pub struct SetIndexExpr {
+    pub object: Box<Expr>,
+    pub bracket: Token,
+    pub index: Box<Expr>,
+    pub value: Box<Expr>,
+}
+
+// `target op= value`, e.g. `a += 1` or `xs[i] *= 2`. The target is a
+// Variable or Index expression and is evaluated only once.
+#[derive(Debug, Clone)]
+pub struct CompoundAssignExpr {
+    pub target: Box<Expr>,
+    pub operator: Token,
+    pub value: Box<Expr>,
+}
+
+// `[a, b] = value`: assigns the parts of value to existing variables.
+#[derive(Debug, Clone)]
+pub struct DestructureAssignExpr {
+    pub pattern: Box<Pattern>,
+    pub value: Box<Expr>,
+}
+
+// `++target`, `target++`, `--target` or `target--`.
+#[derive(Debug, Clone)]
+pub struct IncrementExpr {
+    pub target: Box<Expr>,
+    pub operator: Token,
+    pub prefix: bool,
 }
 
 // --- BASE EXPR ENUM ---
@@ -71,6 +138,13 @@ pub enum Expr {
     Assign(AssignExpr),
     Logical(LogicalExpr),
     Call(CallExpr),
+    Get(GetExpr),
+    List(ListExpr),
+    Index(IndexExpr),
+    SetIndex(SetIndexExpr),
+    CompoundAssign(CompoundAssignExpr),
+    Increment(IncrementExpr),
+    DestructureAssign(DestructureAssignExpr),
     // You'll add more variants here as you expand Lox (e.g., Variable, Call, Assign)
 }
 
@@ -88,6 +162,13 @@ pub trait Visitor<R> {
     fn visit_assign_expr(&mut self, expr: &AssignExpr) -> R;
     fn visit_logical_expr(&mut self, expr: &LogicalExpr) -> R;
     fn visit_call_expr(&mut self, expr: &CallExpr) -> R;
+    fn visit_get_expr(&mut self, expr: &GetExpr) -> R;
+    fn visit_list_expr(&mut self, expr: &ListExpr) -> R;
+    fn visit_index_expr(&mut self, expr: &IndexExpr) -> R;
+    fn visit_set_index_expr(&mut self, expr: &SetIndexExpr) -> R;
+    fn visit_compound_assign_expr(&mut self, expr: &CompoundAssignExpr) -> R;
+    fn visit_increment_expr(&mut self, expr: &IncrementExpr) -> R;
+    fn visit_destructure_assign_expr(&mut self, expr: &DestructureAssignExpr) -> R;
 }
 
 impl Expr {
@@ -104,6 +185,13 @@ impl Expr {
             Expr::Assign(assign) => visitor.visit_assign_expr(assign),
             Expr::Logical(logical) => visitor.visit_logical_expr(logical),
             Expr::Call(call) => visitor.visit_call_expr(call),
+            Expr::Get(get) => visitor.visit_get_expr(get),
+            Expr::List(list) => visitor.visit_list_expr(list),
+            Expr::Index(index) => visitor.visit_index_expr(index),
+            Expr::SetIndex(set) => visitor.visit_set_index_expr(set),
+            Expr::CompoundAssign(assign) => visitor.visit_compound_assign_expr(assign),
+            Expr::Increment(inc) => visitor.visit_increment_expr(inc),
+            Expr::DestructureAssign(assign) => visitor.visit_destructure_assign_expr(assign),
         }
     }
 }
\ No newline at end of file
@@ -1,3 +1,7 @@
 pub mod expr;
 pub mod parser;
-pub mod stmt;
\ No newline at end of file
+pub mod stmt;
+pub mod resolver;
+pub mod pattern;
+pub mod types;
+pub mod type_checker;
\ No newline at end of file
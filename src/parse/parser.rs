@@ -1,7 +1,12 @@
 use crate::input::scanner::Scanner;
 use crate::token::token::{Token, TokenType};
-use crate::parse::expr::{Expr, BinaryExpr, UnaryExpr, GroupingExpr, LiteralExpr, LiteralValue, AssignExpr};
-use crate::parse::stmt::Stmt;
+use crate::parse::expr::{Expr, BinaryExpr, UnaryExpr, GroupingExpr, LiteralExpr, LiteralValue, AssignExpr, DestructureAssignExpr, ListExpr, IndexExpr, SetIndexExpr, CompoundAssignExpr, IncrementExpr};
+use crate::parse::stmt::{Stmt, Param, MatchArm};
+use crate::parse::pattern::Pattern;
+use crate::parse::resolver::Resolver;
+use crate::parse::type_checker::TypeChecker;
+use crate::parse::types::Type;
+use crate::interpret::bigint::BigInt;
 use crate::util::logger::LogLevel;
 
 #[derive(Debug, Clone)]
@@ -16,6 +21,10 @@ pub struct Parser {
     // #[allow(dead_code)]
     errors: Vec<ParseError>,
     had_error: bool,
+    // Static checks on each parsed statement (e.g. assignment to constants)
+    resolver: Resolver,
+    // Checks statements against their type annotations
+    type_checker: TypeChecker,
 }
 
 impl Parser {
@@ -26,6 +35,8 @@ impl Parser {
             token_source,
             errors: Vec::new(),
             had_error: false,
+            resolver: Resolver::new(),
+            type_checker: TypeChecker::new(),
         }
     }
 
@@ -71,6 +82,11 @@ impl Parser {
         self.had_error = false;
     }
 
+    // The first recorded error, formatted like report_errors() does
+    pub fn first_error(&self) -> Option<String> {
+        self.errors.first().map(|error| format!("[line {}] Error at '{}': {}", error.line, error.token.lexeme, error.message))
+    }
+
     pub fn clear_errors(&mut self) {
         self.errors.clear();
     }
@@ -108,11 +124,17 @@ impl Parser {
                 TokenType::Class
                 | TokenType::Fun
                 | TokenType::Var
+                | TokenType::Const
                 | TokenType::For
                 | TokenType::If
                 | TokenType::While
                 | TokenType::Print
-                | TokenType::Return => {
+                | TokenType::Return
+                | TokenType::Yield
+                | TokenType::Throw
+                | TokenType::Match
+                | TokenType::Import
+                | TokenType::Try => {
                     return;
                 }
                 _ => {}
@@ -125,7 +147,21 @@ impl Parser {
     // Parse a full program: a sequence of statements until EOF.
     pub fn parse(&mut self) -> Option<Stmt> {
         // either a declaration or a statement.
-        self.declaration()
+        let stmt = self.declaration()?;
+
+        // Reject statements that fail the static checks before they run.
+        // Parsing already finished, so there is nothing to synchronize.
+        let mut problems = self.resolver.resolve(&stmt);
+        problems.extend(self.type_checker.check(&stmt));
+        if problems.is_empty() {
+            return Some(stmt);
+        }
+        for (token, message) in problems {
+            let line = token.line;
+            self.errors.push(ParseError { token, message, line });
+        }
+        self.had_error = true;
+        None
     }
 
     // Parse a declaration (top-level): currently only var declarations or statements.
@@ -133,17 +169,36 @@ impl Parser {
         if self.match_token(&[TokenType::Fun]) {
             // consume 'fun'
             let _ = self.token_source.next_token();
-            return self.function("function");
+            // `fun*` declares a generator function
+            if self.match_token(&[TokenType::Star]) {
+                let _ = self.token_source.next_token();
+                return self.function("function", true);
+            }
+            return self.function("function", false);
         }
         if self.match_token(&[TokenType::Var]) {
             // consume the 'var' keyword
             let _ = self.token_source.next_token();
+            // `var [a, b] = ...` and `var {x, y} = ...` destructure the value
+            if self.match_token(&[TokenType::LeftBracket, TokenType::LeftBrace]) {
+                return self.var_pattern_declaration();
+            }
             return self.var_declaration();
         }
+        if self.match_token(&[TokenType::Import]) {
+            // consume the 'import' keyword
+            let kw = self.token_source.next_token().unwrap();
+            return self.import_declaration(kw);
+        }
+        if self.match_token(&[TokenType::Const]) {
+            // consume the 'const' keyword
+            let _ = self.token_source.next_token();
+            return self.const_declaration();
+        }
         return self.statement();
     }
 
-    fn function(&mut self, kind: &str) -> Option<Stmt> {
+    fn function(&mut self, kind: &str, generator: bool) -> Option<Stmt> {
         // Expect function name
         let name = match self.consume(TokenType::Identifier, &format!("Expect {} name.", kind)) {
             Some(t) => t,
@@ -155,7 +210,8 @@ impl Parser {
             return None;
         }
 
-        let mut parameters: Vec<Token> = Vec::new();
+        let mut parameters: Vec<Param> = Vec::new();
+        let mut rest: Option<Token> = None;
         if !self.match_token(&[TokenType::RightParen]) {
             loop {
                 if parameters.len() >= 255 {
@@ -165,11 +221,39 @@ impl Parser {
                     }
                 }
 
-                let param = match self.consume(TokenType::Identifier, "Expect parameter name.") {
-                    Some(t) => t,
-                    None => return None,
-                };
-                parameters.push(param);
+                // '...rest' collects any extra arguments and must come last
+                if self.match_token(&[TokenType::Ellipsis]) {
+                    let _ = self.token_source.next_token();
+                    rest = match self.consume(TokenType::Identifier, "Expect rest parameter name after '...'.") {
+                        Some(t) => Some(t),
+                        None => return None,
+                    };
+                    if !self.match_token(&[TokenType::RightParen]) {
+                        if let Some(tok) = self.token_source.peek_token() {
+                            self.error(tok, "Rest parameter must be the last parameter.");
+                        }
+                        return None;
+                    }
+                    break;
+                }
+
+                let name = self.consume(TokenType::Identifier, "Expect parameter name.")?;
+                let ty = self.type_annotation()?;
+
+                // Optional '= default'; once one parameter has a default,
+                // the ones after it need one too
+                let mut default: Option<Expr> = None;
+                if self.match_token(&[TokenType::Equal]) {
+                    let _ = self.token_source.next_token();
+                    default = match self.expression() {
+                        Some(expr) => Some(expr),
+                        None => return None,
+                    };
+                } else if parameters.iter().any(|p| p.default.is_some()) {
+                    self.error(name, "Parameter without a default can't follow one with a default.");
+                    return None;
+                }
+                parameters.push(Param { name, ty, default });
 
                 if self.match_token(&[TokenType::Comma]) {
                     let _ = self.token_source.next_token();
@@ -182,6 +266,7 @@ impl Parser {
         if self.consume(TokenType::RightParen, "Expect ')' after parameters.").is_none() {
             return None;
         }
+        let return_type = self.type_annotation()?;
 
         // Expect '{' before function body
         if self.consume(TokenType::LeftBrace, &format!("Expect '{{' before {} body.", kind)).is_none() {
@@ -189,7 +274,7 @@ impl Parser {
         }
 
         let body = self.block();
-        Some(Stmt::Function { name, params: parameters, body })
+        Some(Stmt::Function { name, params: parameters, rest, return_type, body, generator })
     }
 
     fn return_statement(&mut self, keyword: crate::token::token::Token) -> Option<Stmt> {
@@ -209,12 +294,33 @@ impl Parser {
         Some(Stmt::Return { keyword, value })
     }
 
+    fn yield_statement(&mut self, keyword: Token) -> Option<Stmt> {
+        let mut value: Option<Expr> = None;
+        if !self.match_token(&[TokenType::Semicolon]) {
+            if let Some(expr) = self.expression() {
+                value = Some(expr);
+            } else {
+                return None;
+            }
+        }
+
+        self.consume(TokenType::Semicolon, "Expect ';' after yield value.")?;
+
+        Some(Stmt::Yield { keyword, value })
+    }
+
     fn var_declaration(&mut self) -> Option<Stmt> {
         // Expect an identifier
         let name = match self.consume(TokenType::Identifier, "Expect variable name.") {
             Some(t) => t,
             None => return None,
         };
+        self.finish_var_declaration(name)
+    }
+
+    // The rest of a var declaration after its name
+    fn finish_var_declaration(&mut self, name: Token) -> Option<Stmt> {
+        let ty = self.type_annotation()?;
 
         // Optional initializer
         let mut initializer: Option<Expr> = None;
@@ -234,7 +340,81 @@ impl Parser {
             return None;
         }
 
-        Some(Stmt::Var { name, initializer })
+        Some(Stmt::Var { name, ty, initializer })
+    }
+
+    // Optional `: type` after a name. Returns Some(None) when there is no
+    // annotation and None after reporting a malformed one.
+    fn type_annotation(&mut self) -> Option<Option<Type>> {
+        if !self.match_token(&[TokenType::Colon]) {
+            return Some(None);
+        }
+        let _ = self.token_source.next_token();
+        self.type_name().map(Some)
+    }
+
+    // type: name '?'?
+    fn type_name(&mut self) -> Option<Type> {
+        let token = self.token_source.peek_token()?;
+        let base = match token.get_type() {
+            TokenType::Nil => Type::Nil,
+            TokenType::Identifier => match Type::from_name(&token.lexeme) {
+                Some(ty) => ty,
+                None => {
+                    let message = format!("Unknown type '{}'.", token.lexeme);
+                    self.error(token, &message);
+                    return None;
+                }
+            },
+            _ => {
+                self.error(token, "Expect type name.");
+                return None;
+            }
+        };
+        let _ = self.token_source.next_token();
+        if self.match_token(&[TokenType::Question]) {
+            let _ = self.token_source.next_token();
+            return Some(Type::Optional(Box::new(base)));
+        }
+        Some(base)
+    }
+
+    fn var_pattern_declaration(&mut self) -> Option<Stmt> {
+        let pattern = self.pattern()?;
+        if let Some(token) = pattern.first_refutable() {
+            self.error(token.clone(), "Only names, '_', lists and maps can be destructured.");
+            return None;
+        }
+
+        // Unlike a plain var, there is nothing to bind without an initializer
+        self.consume(TokenType::Equal, "Expect '=' after destructuring pattern.")?;
+        let initializer = self.expression()?;
+
+        self.consume(TokenType::Semicolon, "Expect ';' after variable declaration.")?;
+
+        Some(Stmt::VarPattern { pattern, initializer })
+    }
+
+    // import "path" as name;
+    fn import_declaration(&mut self, keyword: Token) -> Option<Stmt> {
+        let path = self.consume(TokenType::String, "Expect module path string after 'import'.")?;
+        self.consume(TokenType::As, "Expect 'as' after module path.")?;
+        let name = self.consume(TokenType::Identifier, "Expect module name after 'as'.")?;
+        self.consume(TokenType::Semicolon, "Expect ';' after import.")?;
+        Some(Stmt::Import { keyword, path, name })
+    }
+
+    fn const_declaration(&mut self) -> Option<Stmt> {
+        let name = self.consume(TokenType::Identifier, "Expect constant name.")?;
+        let ty = self.type_annotation()?;
+
+        // Unlike var, a constant must be initialized
+        self.consume(TokenType::Equal, "Expect '=' after constant name.")?;
+        let initializer = self.expression()?;
+
+        self.consume(TokenType::Semicolon, "Expect ';' after constant declaration.")?;
+
+        Some(Stmt::Const { name, ty, initializer })
     }
 
     fn statement(&mut self) -> Option<Stmt> {
@@ -249,6 +429,21 @@ impl Parser {
             let kw = self.token_source.next_token().unwrap();
             return self.return_statement(kw);
         }
+        if self.match_token(&[TokenType::Yield]) {
+            // consume 'yield'
+            let kw = self.token_source.next_token().unwrap();
+            return self.yield_statement(kw);
+        }
+        if self.match_token(&[TokenType::Throw]) {
+            // consume 'throw'
+            let kw = self.token_source.next_token().unwrap();
+            return self.throw_statement(kw);
+        }
+        if self.match_token(&[TokenType::Try]) {
+            // consume 'try'
+            let _ = self.token_source.next_token();
+            return self.try_statement();
+        }
         if self.match_token(&[TokenType::For]) {
             // consume 'for'
             let _ = self.token_source.next_token();
@@ -264,6 +459,11 @@ impl Parser {
             let _ = self.token_source.next_token();
             return self.while_statement();
         }
+        if self.match_token(&[TokenType::Match]) {
+            // consume 'match'
+            let kw = self.token_source.next_token().unwrap();
+            return self.match_statement(kw);
+        }
         // Block statement
         if self.match_token(&[TokenType::LeftBrace]) {
             // consume '{'
@@ -274,6 +474,167 @@ impl Parser {
         return self.expression_statement();
     }
 
+    fn match_statement(&mut self, keyword: Token) -> Option<Stmt> {
+        self.consume(TokenType::LeftParen, "Expect '(' after 'match'.")?;
+        let subject = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after match value.")?;
+        self.consume(TokenType::LeftBrace, "Expect '{' before match arms.")?;
+
+        // pattern ('if' guard)? '=>' statement, optionally followed by ','
+        let mut arms: Vec<MatchArm> = Vec::new();
+        while !self.match_token(&[TokenType::RightBrace, TokenType::Eof]) {
+            let pattern = self.pattern()?;
+            let mut guard: Option<Expr> = None;
+            if self.match_token(&[TokenType::If]) {
+                let _ = self.token_source.next_token();
+                guard = Some(self.expression()?);
+            }
+            self.consume(TokenType::FatArrow, "Expect '=>' after match pattern.")?;
+            let body = self.statement()?;
+            arms.push(MatchArm { pattern, guard, body });
+            if self.match_token(&[TokenType::Comma]) {
+                let _ = self.token_source.next_token();
+            }
+        }
+
+        self.consume(TokenType::RightBrace, "Expect '}' after match arms.")?;
+        Some(Stmt::Match { keyword, subject, arms })
+    }
+
+    // pattern: alternative ('|' alternative)*
+    fn pattern(&mut self) -> Option<Pattern> {
+        let first = self.pattern_alternative()?;
+        if !self.match_token(&[TokenType::Pipe]) {
+            return Some(first);
+        }
+        let mut alternatives = vec![first];
+        while self.match_token(&[TokenType::Pipe]) {
+            let _ = self.token_source.next_token();
+            alternatives.push(self.pattern_alternative()?);
+        }
+        Some(Pattern::Or(alternatives))
+    }
+
+    // '_' | name | literal | '-' number | '[' patterns (',' '...' name)? ']'
+    fn pattern_alternative(&mut self) -> Option<Pattern> {
+        let token = self.token_source.peek_token()?;
+        match token.get_type() {
+            TokenType::Identifier => {
+                let _ = self.token_source.next_token();
+                if token.lexeme == "_" {
+                    Some(Pattern::Wildcard(token))
+                } else {
+                    Some(Pattern::Binding(token))
+                }
+            }
+            TokenType::Number | TokenType::String | TokenType::True | TokenType::False | TokenType::Nil => {
+                let value = self.primary()?;
+                Some(Pattern::Literal { token, value })
+            }
+            TokenType::Minus => {
+                let _ = self.token_source.next_token();
+                if !self.match_token(&[TokenType::Number]) {
+                    self.error(token, "Expect number after '-' in pattern.");
+                    return None;
+                }
+                let number = self.primary()?;
+                let value = Expr::Unary(UnaryExpr { operator: token.clone(), right: Box::new(number) });
+                Some(Pattern::Literal { token, value })
+            }
+            TokenType::LeftBracket => {
+                let _ = self.token_source.next_token();
+                let mut elements: Vec<Pattern> = Vec::new();
+                let mut rest: Option<Token> = None;
+                while !self.match_token(&[TokenType::RightBracket]) {
+                    // '...rest' collects the remaining elements and must come last
+                    if self.match_token(&[TokenType::Ellipsis]) {
+                        let _ = self.token_source.next_token();
+                        rest = Some(self.consume(TokenType::Identifier, "Expect name after '...'.")?);
+                        break;
+                    }
+                    elements.push(self.pattern()?);
+                    if !self.match_token(&[TokenType::Comma]) {
+                        break;
+                    }
+                    let _ = self.token_source.next_token();
+                }
+                self.consume(TokenType::RightBracket, "Expect ']' after list pattern.")?;
+                Some(Pattern::List { bracket: token, elements, rest })
+            }
+            TokenType::LeftBrace => {
+                let _ = self.token_source.next_token();
+                let mut entries: Vec<(Token, Pattern)> = Vec::new();
+                while !self.match_token(&[TokenType::RightBrace]) {
+                    // `key` binds the value to that name, `key: pattern` matches it further
+                    let key = self.consume(TokenType::Identifier, "Expect key name in map pattern.")?;
+                    let element = if self.match_token(&[TokenType::Colon]) {
+                        let _ = self.token_source.next_token();
+                        self.pattern()?
+                    } else {
+                        Pattern::Binding(key.clone())
+                    };
+                    entries.push((key, element));
+                    if !self.match_token(&[TokenType::Comma]) {
+                        break;
+                    }
+                    let _ = self.token_source.next_token();
+                }
+                self.consume(TokenType::RightBrace, "Expect '}' after map pattern.")?;
+                Some(Pattern::Map { brace: token, entries })
+            }
+            _ => {
+                self.error(token, "Expect pattern.");
+                None
+            }
+        }
+    }
+
+    fn throw_statement(&mut self, keyword: Token) -> Option<Stmt> {
+        let value = self.expression()?;
+
+        self.consume(TokenType::Semicolon, "Expect ';' after thrown value.")?;
+
+        Some(Stmt::Throw { keyword, value })
+    }
+
+    fn try_statement(&mut self) -> Option<Stmt> {
+        self.consume(TokenType::LeftBrace, "Expect '{' after 'try'.")?;
+        let body = self.block();
+
+        // Optional catch clause with its binding: catch (name) { ... }
+        let mut catch_name: Option<Token> = None;
+        let mut catch_body: Option<Vec<Stmt>> = None;
+        if self.match_token(&[TokenType::Catch]) {
+            // consume 'catch'
+            let _ = self.token_source.next_token();
+            self.consume(TokenType::LeftParen, "Expect '(' after 'catch'.")?;
+            catch_name = match self.consume(TokenType::Identifier, "Expect error variable name.") {
+                Some(t) => Some(t),
+                None => return None,
+            };
+            self.consume(TokenType::RightParen, "Expect ')' after error variable.")?;
+            self.consume(TokenType::LeftBrace, "Expect '{' before catch body.")?;
+            catch_body = Some(self.block());
+        }
+
+        // Optional finally clause
+        let mut finally_body: Option<Vec<Stmt>> = None;
+        if self.match_token(&[TokenType::Finally]) {
+            // consume 'finally'
+            let _ = self.token_source.next_token();
+            self.consume(TokenType::LeftBrace, "Expect '{' after 'finally'.")?;
+            finally_body = Some(self.block());
+        }
+
+        if catch_body.is_none() && finally_body.is_none() {
+            let tok = self.token_source.peek_token().unwrap_or(Token::new_token(TokenType::Eof, "".to_string(), None, 0));
+            self.error(tok, "Expect 'catch' or 'finally' after try block.");
+            return None;
+        }
+
+        Some(Stmt::Try { body, catch_name, catch_body, finally_body })
+    }
+
     fn if_statement(&mut self) -> Option<Stmt> {
         // Expect '('
         if self.consume(TokenType::LeftParen, "Expect '(' after 'if'.").is_none() {
@@ -339,7 +700,8 @@ impl Parser {
             return None;
         }
 
-        // Initializer: can be ';' (none), a var declaration, or an expression statement
+        // Initializer: can be ';' (none), a var declaration, or an expression
+        // statement. `name in` (optionally `var name in`) starts a for-in loop.
         let initializer: Option<Stmt>;
         if self.match_token(&[TokenType::Semicolon]) {
             // consume ';'
@@ -348,9 +710,18 @@ impl Parser {
         } else if self.match_token(&[TokenType::Var]) {
             // consume 'var'
             let _ = self.token_source.next_token();
-            initializer = self.var_declaration();
+            let name = self.consume(TokenType::Identifier, "Expect variable name.")?;
+            if self.match_token(&[TokenType::In]) {
+                return self.for_in_statement(name);
+            }
+            initializer = self.finish_var_declaration(name);
         } else {
-            initializer = self.expression_statement();
+            let expr = self.expression()?;
+            if let Expr::Variable(name) = &expr && self.match_token(&[TokenType::In]) {
+                return self.for_in_statement(name.clone());
+            }
+            self.consume(TokenType::Semicolon, "Expect ';' after expression.")?;
+            initializer = Some(Stmt::Expression(expr));
         }
 
         // Condition
@@ -403,6 +774,16 @@ impl Parser {
         Some(body)
     }
 
+    // for (name in iterable) body, after the loop variable's name
+    fn for_in_statement(&mut self, name: Token) -> Option<Stmt> {
+        // consume 'in'
+        let _ = self.token_source.next_token();
+        let iterable = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after for-in iterable.")?;
+        let body = self.statement()?;
+        Some(Stmt::ForIn { name, iterable, body: Box::new(body) })
+    }
+
     fn block(&mut self) -> Vec<Stmt> {
         let mut statements: Vec<Stmt> = Vec::new();
 
@@ -460,8 +841,8 @@ impl Parser {
     }
 
     fn assignment(&mut self) -> Option<Expr> {
-        // Parse the left-hand side as a logic_or (higher precedence than assignment)
-        let expr = self.logic_or();
+        // Parse the left-hand side as a coalesce (higher precedence than assignment)
+        let expr = self.coalesce();
         if expr.is_none() {
             return None;
         }
@@ -480,6 +861,19 @@ impl Parser {
                     Expr::Variable(name) => {
                         return Some(Expr::Assign(AssignExpr { name, value: Box::new(val_expr) }));
                     }
+                    Expr::Index(IndexExpr { object, bracket, index }) => {
+                        return Some(Expr::SetIndex(SetIndexExpr { object, bracket, index, value: Box::new(val_expr) }));
+                    }
+                    // `[a, b] = [b, a]` assigns the parts of the value
+                    Expr::List(list) => match Self::list_assignment_pattern(&list) {
+                        Some(pattern) => {
+                            return Some(Expr::DestructureAssign(DestructureAssignExpr { pattern: Box::new(pattern), value: Box::new(val_expr) }));
+                        }
+                        None => {
+                            self.error(equals, "Invalid assignment target.");
+                            return None;
+                        }
+                    },
                     _ => {
                         self.error(equals, "Invalid assignment target.");
                         return None;
@@ -492,10 +886,72 @@ impl Parser {
             }
         }
 
+        // Compound assignment: `a += 1`, `xs[i] *= 2`, ...
+        if self.match_token(&[TokenType::PlusEqual, TokenType::MinusEqual, TokenType::StarEqual, TokenType::SlashEqual, TokenType::PercentEqual]) {
+            let operator = self.token_source.next_token().unwrap();
+            let value = self.assignment();
+            let target = expr.unwrap();
+            if !Self::is_assignable(&target) {
+                self.error(operator, "Invalid assignment target.");
+                return None;
+            }
+            match value {
+                Some(val_expr) => {
+                    return Some(Expr::CompoundAssign(CompoundAssignExpr {
+                        target: Box::new(target),
+                        operator,
+                        value: Box::new(val_expr),
+                    }));
+                }
+                None => {
+                    let message = format!("Expect expression after '{}'.", operator.lexeme);
+                    self.error(operator, &message);
+                    return None;
+                }
+            }
+        }
+
         // No assignment; return the previously parsed expression
         expr
     }
 
+    // Targets that compound assignment and ++/-- can update
+    fn is_assignable(expr: &Expr) -> bool {
+        matches!(expr, Expr::Variable(_) | Expr::Index(_))
+    }
+
+    // The pattern a list literal stands for on the left of '=': each element
+    // must be a variable, `_` or another such list
+    fn list_assignment_pattern(list: &ListExpr) -> Option<Pattern> {
+        let mut elements: Vec<Pattern> = Vec::new();
+        for element in &list.elements {
+            elements.push(match element {
+                Expr::Variable(name) if name.lexeme == "_" => Pattern::Wildcard(name.clone()),
+                Expr::Variable(name) => Pattern::Binding(name.clone()),
+                Expr::List(inner) => Self::list_assignment_pattern(inner)?,
+                _ => return None,
+            });
+        }
+        Some(Pattern::List { bracket: list.bracket.clone(), elements, rest: None })
+    }
+
+    // `a ?? b`: binds looser than `or`, so `a or b ?? c` is `(a or b) ?? c`
+    fn coalesce(&mut self) -> Option<Expr> {
+        let mut expr = self.logic_or()?;
+
+        while self.match_token(&[TokenType::QuestionQuestion]) {
+            let operator = self.token_source.next_token().unwrap();
+            if let Some(right) = self.logic_or() {
+                expr = Expr::Logical(crate::parse::expr::LogicalExpr { left: Box::new(expr), operator, right: Box::new(right) });
+            } else {
+                self.error(operator, "Expect expression after '??'.");
+                return None;
+            }
+        }
+
+        Some(expr)
+    }
+
     fn logic_or(&mut self) -> Option<Expr> {
         let mut expr = match self.logic_and() {
             Some(e) => e,
@@ -562,7 +1018,7 @@ impl Parser {
     }
 
     fn comparison(&mut self) -> Option<Expr> {
-        let expr = self.term();
+        let expr = self.bit_or();
         // Handle no expr
         if expr.is_none() {
             return None;
@@ -593,6 +1049,41 @@ impl Parser {
         return expr;
     }
 
+    // Bitwise operators bind looser than arithmetic but tighter than comparison,
+    // so `a & 1 == 0` compares the masked value: | < ^ < & < shifts.
+    fn bit_or(&mut self) -> Option<Expr> {
+        self.left_assoc_binary(&[TokenType::Pipe], Self::bit_xor)
+    }
+
+    fn bit_xor(&mut self) -> Option<Expr> {
+        self.left_assoc_binary(&[TokenType::Caret], Self::bit_and)
+    }
+
+    fn bit_and(&mut self) -> Option<Expr> {
+        self.left_assoc_binary(&[TokenType::Ampersand], Self::shift)
+    }
+
+    fn shift(&mut self) -> Option<Expr> {
+        self.left_assoc_binary(&[TokenType::LessLess, TokenType::GreaterGreater], Self::term)
+    }
+
+    // Parse `operand (op operand)*` into left-associative binary expressions
+    fn left_assoc_binary(&mut self, operators: &[TokenType], operand: fn(&mut Self) -> Option<Expr>) -> Option<Expr> {
+        let mut expr = operand(self)?;
+
+        while self.match_token(operators) {
+            let operator = self.token_source.next_token().unwrap();
+            if let Some(right) = operand(self) {
+                expr = Expr::Binary(BinaryExpr { left: Box::new(expr), operator, right: Box::new(right) });
+            } else {
+                self.error(operator, "Expect expression after operator.");
+                return None;
+            }
+        }
+
+        Some(expr)
+    }
+
     fn term(&mut self) -> Option<Expr> {
         let expr = self.factor();
         // Handle no expr
@@ -600,8 +1091,15 @@ impl Parser {
             return None;
         }
 
-        if self.match_token(&[TokenType::Plus, TokenType::Minus]) {
-            let operator = self.token_source.next_token().unwrap();
+        if self.match_token(&[TokenType::Plus, TokenType::Minus, TokenType::MinusMinus]) {
+            let mut operator = self.token_source.next_token().unwrap();
+            // A `--` that postfix() left alone is a minus followed by a
+            // negation, as in `1--1`
+            if operator.get_type() == TokenType::MinusMinus {
+                let minus = Token::new_token(TokenType::Minus, "-".to_string(), None, operator.line);
+                self.token_source.push_back(minus.clone());
+                operator = minus;
+            }
             if let Some(left) = expr {
                 if let Some(right) = self.term() {
                     return Some(Expr::Binary(BinaryExpr {
@@ -620,71 +1118,92 @@ impl Parser {
         return expr;
     }
 
+    // `*`, `/`, `%` and `div` associate to the left: `17 % 5 % 3` is `(17 % 5) % 3`
     fn factor(&mut self) -> Option<Expr> {
-        // 1. Get the left-hand expression result.
-        let expr = self.unary();
-        // Handle no expr
-        if expr.is_none() {
-            return None;
+        self.left_assoc_binary(&[TokenType::Star, TokenType::Slash, TokenType::Percent, TokenType::Div], Self::unary)
+    }
+
+    fn unary(&mut self) -> Option<Expr> {
+        if self.match_token(&[TokenType::Minus, TokenType::Bang, TokenType::Tilde]) {
+            let operator = self.token_source.next_token().unwrap();
+            if let Some(right) = self.unary() {
+                return Some(Expr::Unary(UnaryExpr {
+                    operator,
+                    right: Box::new(right),
+                }));
+            }
         }
 
-        // 2. Check for the operator and consume it.
-        if self.match_token(&[TokenType::Star, TokenType::Slash]) {
-            // NOTE: If match_token returns true, consume the operator token.
+        // Prefix increment/decrement: `++i`, `--xs[0]`
+        if self.match_token(&[TokenType::PlusPlus, TokenType::MinusMinus]) {
             let operator = self.token_source.next_token().unwrap();
+            let target = self.unary()?;
+            // `--` before anything else negates twice, so `--1` is still 1
+            if !Self::is_assignable(&target) && operator.get_type() == TokenType::MinusMinus {
+                let minus = Token::new_token(TokenType::Minus, "-".to_string(), None, operator.line);
+                let inner = Expr::Unary(UnaryExpr { operator: minus.clone(), right: Box::new(target) });
+                return Some(Expr::Unary(UnaryExpr { operator: minus, right: Box::new(inner) }));
+            }
+            if !Self::is_assignable(&target) {
+                let message = format!("Invalid operand for '{}'.", operator.lexeme);
+                self.error(operator, &message);
+                return None;
+            }
+            return Some(Expr::Increment(IncrementExpr { target: Box::new(target), operator, prefix: true }));
+        }
 
-            // 3. Move the value out of 'expr' only if it exists (i.e., unary() succeeded).
-            // This handles the E0382 error by ensuring we don't try to use 'expr' later.
-            let left = match expr {
-                Some(e) => e, // Success: 'e' is the left operand (moved out of 'expr')
-                None => {
-                    // Error Case 1: Operator found, but no left operand (e.g., '* 3').
-                    // We must report an error here since the token was consumed.
-                    self.error(operator, "Expect expression before operator.");
-                    return None; 
+        self.power()
+    }
+
+    // `**` binds tighter than unary minus (`-2 ** 2` is -4) and is right-associative.
+    // The exponent is parsed as a unary so `2 ** -1` works.
+    fn power(&mut self) -> Option<Expr> {
+        let base = self.postfix()?;
+
+        if self.match_token(&[TokenType::StarStar]) {
+            let operator = self.token_source.next_token().unwrap();
+            match self.unary() {
+                Some(exponent) => {
+                    return Some(Expr::Binary(BinaryExpr {
+                        left: Box::new(base),
+                        operator,
+                        right: Box::new(exponent),
+                    }));
                 }
-            };
-            
-            // 4. Recursively parse the right-hand expression.
-            let right = match self.factor() {
-                Some(e) => e,
                 None => {
-                    // Error Case 2: Operator found, but no right operand (e.g., '3 *').
                     self.error(operator, "Expect expression after operator.");
                     return None;
                 }
-            };
-
-            // 5. Success: Construct and return the new Binary expression.
-            return Some(Expr::Binary(BinaryExpr {
-                left: Box::new(left),
-                operator,
-                right: Box::new(right),
-            }));
+            }
         }
 
-        // 6. If the token did NOT match (or if match_token failed), 
-        //    return the original result from unary(). 
-        //    This is safe because 'expr' was only moved inside the 'if' block.
-        return expr;
+        Some(base)
     }
 
-    fn unary(&mut self) -> Option<Expr> {
-        if self.match_token(&[TokenType::Minus, TokenType::Bang]) {
+    fn postfix(&mut self) -> Option<Expr> {
+        // Calls have higher precedence than postfix operators, so parse call expressions here.
+        let expr = self.call()?;
+
+        // Postfix increment/decrement: `i++`, `xs[0]--`. A `--` after
+        // anything else is left for term() to read as `- -`.
+        if self.match_token(&[TokenType::MinusMinus]) && !Self::is_assignable(&expr) {
+            return Some(expr);
+        }
+        if self.match_token(&[TokenType::PlusPlus, TokenType::MinusMinus]) {
             let operator = self.token_source.next_token().unwrap();
-            if let Some(right) = self.unary() {
-                return Some(Expr::Unary(UnaryExpr {
-                    operator,
-                    right: Box::new(right),
-                }));
+            if !Self::is_assignable(&expr) {
+                let message = format!("Invalid operand for '{}'.", operator.lexeme);
+                self.error(operator, &message);
+                return None;
             }
+            return Some(Expr::Increment(IncrementExpr { target: Box::new(expr), operator, prefix: false }));
         }
-
-        // Calls have higher precedence than unary, so parse call expressions here.
-        return self.call();
+        Some(expr)
     }
 
-    // Parse call expressions: primary followed by zero or more argument lists
+    // Parse call expressions: primary followed by zero or more argument lists,
+    // '.name' property accesses or '[index]' subscripts, and their nil-safe
+    // forms '?.(args)' and '?.name'
     fn call(&mut self) -> Option<Expr> {
         let mut expr = match self.primary() {
             Some(e) => e,
@@ -695,10 +1214,33 @@ impl Parser {
             if self.match_token(&[TokenType::LeftParen]) {
                 // consume '('
                 let _ = self.token_source.next_token();
-                expr = match self.finish_call(expr) {
-                    Some(c) => c,
-                    None => return None,
-                };
+                expr = self.finish_call(expr, false)?;
+            } else if self.match_token(&[TokenType::Dot]) {
+                // consume '.' and read the property name
+                let dot = self.token_source.next_token().unwrap();
+                // The scanner keeps a stray '..' as one Dot token
+                if dot.lexeme == ".." {
+                    self.error(dot, "Unexpected '..'.");
+                    return None;
+                }
+                let name = self.consume(TokenType::Identifier, "Expect property name after '.'.")?;
+                expr = Expr::Get(crate::parse::expr::GetExpr { object: Box::new(expr), name, optional: false });
+            } else if self.match_token(&[TokenType::QuestionDot]) {
+                // consume '?.'; a call or a property name follows
+                let _ = self.token_source.next_token();
+                if self.match_token(&[TokenType::LeftParen]) {
+                    let _ = self.token_source.next_token();
+                    expr = self.finish_call(expr, true)?;
+                } else {
+                    let name = self.consume(TokenType::Identifier, "Expect property name or '(' after '?.'.")?;
+                    expr = Expr::Get(crate::parse::expr::GetExpr { object: Box::new(expr), name, optional: true });
+                }
+            } else if self.match_token(&[TokenType::LeftBracket]) {
+                // consume '[' and parse the subscript
+                let _ = self.token_source.next_token();
+                let index = self.expression()?;
+                let bracket = self.consume(TokenType::RightBracket, "Expect ']' after index.")?;
+                expr = Expr::Index(crate::parse::expr::IndexExpr { object: Box::new(expr), bracket, index: Box::new(index) });
             } else {
                 break;
             }
@@ -707,15 +1249,36 @@ impl Parser {
         Some(expr)
     }
 
-    fn finish_call(&mut self, callee: Expr) -> Option<Expr> {
+    fn finish_call(&mut self, callee: Expr, optional: bool) -> Option<Expr> {
         let mut arguments: Vec<Expr> = Vec::new();
+        let mut named: Vec<(Token, Expr)> = Vec::new();
         if !self.match_token(&[TokenType::RightParen]) {
             // Parse at least one argument, then any following comma-separated args
             loop {
-                if let Some(arg) = self.expression() {
-                    arguments.push(arg);
-                } else {
+                let arg = self.expression()?;
+
+                // `name: value` is a named argument. Only a bare identifier
+                // can come before the ':'.
+                if self.match_token(&[TokenType::Colon]) {
+                    let colon = self.token_source.next_token();
+                    let name = match arg {
+                        Expr::Variable(name) => name,
+                        _ => {
+                            if let Some(tok) = colon {
+                                self.error(tok, "Expect parameter name before ':'.");
+                            }
+                            return None;
+                        }
+                    };
+                    let value = self.expression()?;
+                    named.push((name, value));
+                } else if !named.is_empty() {
+                    if let Some(tok) = self.token_source.peek_token() {
+                        self.error(tok, "Positional arguments can't follow named arguments.");
+                    }
                     return None;
+                } else {
+                    arguments.push(arg);
                 }
 
                 if self.match_token(&[TokenType::Comma]) {
@@ -734,7 +1297,7 @@ impl Parser {
             None => return None,
         };
 
-        Some(Expr::Call(crate::parse::expr::CallExpr { callee: Box::new(callee), paren, arguments }))
+        Some(Expr::Call(crate::parse::expr::CallExpr { callee: Box::new(callee), paren, arguments, named, optional }))
     }
 
     fn primary(&mut self) -> Option<Expr> {
@@ -769,6 +1332,27 @@ impl Parser {
                 }
             }
         }
+        // List literal: '[' (expression (',' expression)*)? ']'
+        if self.match_token(&[TokenType::LeftBracket]) {
+            // consume '['
+            let bracket = self.token_source.next_token().unwrap();
+            let mut elements: Vec<Expr> = Vec::new();
+            if !self.match_token(&[TokenType::RightBracket]) {
+                loop {
+                    match self.expression() {
+                        Some(e) => elements.push(e),
+                        None => return None,
+                    }
+                    if self.match_token(&[TokenType::Comma]) {
+                        let _ = self.token_source.next_token();
+                    } else {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RightBracket, "Expect ']' after list elements.")?;
+            return Some(Expr::List(ListExpr { bracket, elements }));
+        }
         // False, True, Nil
         if self.match_token(&[TokenType::False]) {
             let _token = self.token_source.next_token();
@@ -792,12 +1376,33 @@ impl Parser {
             let token = self.token_source.peek_token().unwrap();
             match token.get_type() {
                 TokenType::Number => {
-                    let number_content = token.lexeme.parse::<f64>().ok()?;
-                    // Use the token after parsing
+                    // The scanner stores the value as canonical text in the literal
                     self.token_source.next_token();
-                    return Some(Expr::Literal(LiteralExpr {
-                        value: Some(LiteralValue::Number(number_content)),
-                    }));
+                    let text = match token.literal.as_deref() {
+                        Some(text) => text.to_string(),
+                        None => {
+                            // Malformed literal; the scanner recorded why
+                            let message = self.token_source.take_errors().pop()
+                                .unwrap_or_else(|| format!("Invalid number literal '{}'.", token.lexeme));
+                            self.error(token, &message);
+                            return None;
+                        }
+                    };
+                    // Fractional literals always contain '.' or an exponent
+                    let value = if text.contains(['.', 'e', 'E']) {
+                        text.parse::<f64>().ok().map(LiteralValue::Number)
+                    } else {
+                        match text.parse::<i64>() {
+                            Ok(n) => Some(LiteralValue::Int(n)),
+                            Err(_) => BigInt::parse(&text).map(LiteralValue::BigInt),
+                        }
+                    };
+                    if value.is_none() {
+                        let message = format!("Invalid number literal '{}'.", token.lexeme);
+                        self.error(token, &message);
+                        return None;
+                    }
+                    return Some(Expr::Literal(LiteralExpr { value }));
                 }
                 TokenType::String => {
                     // Use the token after parsing
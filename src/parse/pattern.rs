@@ -0,0 +1,108 @@
+use crate::parse::expr::Expr;
+use crate::token::token::Token;
+
+// Pattern tested against a value by a `match` arm, e.g. `[first, ...rest]`
+// or `"a" | "b"`, or taken apart by destructuring (`var [a, b] = pair;`).
+// Names in a pattern bind the matching part of the value.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    // `_`: matches anything and binds nothing
+    Wildcard(Token),
+    // A name: matches anything and binds it
+    Binding(Token),
+    // A number, string, true, false or nil, compared with is_equal
+    Literal { token: Token, value: Expr },
+    // `[a, b]` matches a list of exactly two elements; with `...rest` it
+    // matches at least that many and binds the others to `rest`
+    List { bracket: Token, elements: Vec<Pattern>, rest: Option<Token> },
+    // `{x, y: py}` matches a map with those keys, binding each value to the
+    // key's name or to the pattern after ':'
+    Map { brace: Token, entries: Vec<(Token, Pattern)> },
+    // `p | q`: matches when any alternative does
+    Or(Vec<Pattern>),
+}
+
+impl Pattern {
+    // Whether the pattern matches every value
+    pub fn is_irrefutable(&self) -> bool {
+        match self {
+            Pattern::Wildcard(_) | Pattern::Binding(_) => true,
+            Pattern::Literal { .. } | Pattern::List { .. } | Pattern::Map { .. } => false,
+            Pattern::Or(alternatives) => alternatives.iter().any(Pattern::is_irrefutable),
+        }
+    }
+
+    // Names the pattern can bind, in source order
+    pub fn bindings(&self) -> Vec<Token> {
+        let mut names = Vec::new();
+        self.collect_bindings(&mut names);
+        names
+    }
+
+    fn collect_bindings(&self, names: &mut Vec<Token>) {
+        match self {
+            Pattern::Binding(name) => names.push(name.clone()),
+            Pattern::List { elements, rest, .. } => {
+                for element in elements {
+                    element.collect_bindings(names);
+                }
+                if let Some(rest) = rest.as_ref().filter(|r| r.lexeme != "_") {
+                    names.push(rest.clone());
+                }
+            }
+            Pattern::Map { entries, .. } => {
+                for (_, element) in entries {
+                    element.collect_bindings(names);
+                }
+            }
+            Pattern::Or(alternatives) => {
+                for alternative in alternatives {
+                    alternative.collect_bindings(names);
+                }
+            }
+            Pattern::Wildcard(_) | Pattern::Literal { .. } => {}
+        }
+    }
+
+    // The first literal or alternative in the pattern. Destructuring only
+    // takes values apart, so it rejects patterns that could fail to match.
+    pub fn first_refutable(&self) -> Option<&Token> {
+        match self {
+            Pattern::Wildcard(_) | Pattern::Binding(_) => None,
+            Pattern::Literal { token, .. } => Some(token),
+            Pattern::List { elements, .. } => elements.iter().find_map(Pattern::first_refutable),
+            Pattern::Map { entries, .. } => entries.iter().find_map(|(_, element)| element.first_refutable()),
+            Pattern::Or(_) => Some(self.token()),
+        }
+    }
+
+    // The first alternative of an or-pattern (at any depth) that binds
+    // different names from the alternative before it. Whichever alternative
+    // matches, the arm must see the same names.
+    pub fn mismatched_alternative(&self) -> Option<&Pattern> {
+        match self {
+            Pattern::Wildcard(_) | Pattern::Binding(_) | Pattern::Literal { .. } => None,
+            Pattern::List { elements, .. } => elements.iter().find_map(Pattern::mismatched_alternative),
+            Pattern::Map { entries, .. } => entries.iter().find_map(|(_, element)| element.mismatched_alternative()),
+            Pattern::Or(alternatives) => {
+                let names = |pattern: &Pattern| {
+                    let mut names: Vec<String> = pattern.bindings().into_iter().map(|name| name.lexeme).collect();
+                    names.sort();
+                    names.dedup();
+                    names
+                };
+                alternatives.windows(2).find(|pair| names(&pair[0]) != names(&pair[1])).map(|pair| &pair[1])
+                    .or_else(|| alternatives.iter().find_map(Pattern::mismatched_alternative))
+            }
+        }
+    }
+
+    // Where to report problems with the pattern
+    pub fn token(&self) -> &Token {
+        match self {
+            Pattern::Wildcard(token) | Pattern::Binding(token) => token,
+            Pattern::Literal { token, .. } | Pattern::List { bracket: token, .. } | Pattern::Map { brace: token, .. } => token,
+            Pattern::Or(alternatives) => alternatives[0].token(),
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+use crate::parse::expr::{Expr, Visitor, BinaryExpr, GroupingExpr, LiteralExpr, UnaryExpr, AssignExpr, LogicalExpr, CallExpr, GetExpr, ListExpr, IndexExpr, SetIndexExpr, CompoundAssignExpr, IncrementExpr, DestructureAssignExpr};
+use crate::parse::stmt::{Stmt, Param, MatchArm, Visitor as StmtVisitor};
+use crate::parse::pattern::Pattern;
+use crate::parse::types::Type;
+use crate::token::token::Token;
+use crate::util::logger::{global_logger, LogLevel};
+
+// What the resolver knows about a declared name
+struct Local {
+    is_const: bool,
+    // Whether the name has been given a value by the statements seen so far
+    initialized: bool,
+    // Function nesting depth of the declaration
+    depth: usize,
+}
+
+// Static checks run on each statement after it is parsed, before it is
+// executed. Tracks which names are constants in each lexical scope so that
+// assignments to them are reported up front. Names it hasn't seen (natives,
+// globals declared later) are left to the runtime checks in Environment.
+//
+// It also warns (without rejecting the statement) when a variable declared
+// without an initializer is read before anything assigns it.
+pub struct Resolver {
+    // Innermost scope last. The first scope holds globals and persists
+    // between statements so the REPL and files behave the same.
+    scopes: Vec<HashMap<String, Local>>,
+    errors: Vec<(Token, String)>,
+    function_depth: usize,
+    // Whether the innermost function is a generator (`fun*`)
+    in_generator: bool,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Resolver { scopes: vec![HashMap::new()], errors: Vec::new(), function_depth: 0, in_generator: false }
+    }
+
+    // Check one top-level statement and return the errors found in it
+    pub fn resolve(&mut self, stmt: &Stmt) -> Vec<(Token, String)> {
+        stmt.accept(self);
+        // Drop any scopes left open by an earlier error
+        self.scopes.truncate(1);
+        self.function_depth = 0;
+        self.in_generator = false;
+        std::mem::take(&mut self.errors)
+    }
+
+    fn resolve_stmts(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            stmt.accept(self);
+        }
+    }
+
+    fn resolve_block(&mut self, statements: &[Stmt]) {
+        self.scopes.push(HashMap::new());
+        self.resolve_stmts(statements);
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: &Token, is_const: bool, initialized: bool) {
+        let depth = self.function_depth;
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.lexeme.clone(), Local { is_const, initialized, depth });
+        }
+    }
+
+    fn lookup(&mut self, name: &Token) -> Option<&mut Local> {
+        self.scopes.iter_mut().rev().find_map(|scope| scope.get_mut(&name.lexeme))
+    }
+
+    // Report an assignment to a name that resolves to a constant, and
+    // otherwise remember that the name now has a value
+    fn check_assignable(&mut self, name: &Token) {
+        let is_const = match self.lookup(name) {
+            Some(local) => {
+                local.initialized = true;
+                local.is_const
+            }
+            None => return,
+        };
+        if is_const {
+            self.errors.push((name.clone(), format!("Cannot assign to constant '{}'.", name.lexeme)));
+        }
+    }
+
+    // Warn about reading a variable that hasn't been assigned yet. Reads from
+    // inside a nested function are skipped since it may run after the
+    // assignment.
+    fn check_initialized(&mut self, name: &Token) {
+        let depth = self.function_depth;
+        let uninitialized = match self.lookup(name) {
+            Some(local) => !local.initialized && local.depth == depth,
+            None => false,
+        };
+        if uninitialized {
+            global_logger().log(LogLevel::Warn, format!("resolver: [line {}] Warning at '{}': Variable '{}' may be used before initialization.", name.line, name.lexeme, name.lexeme));
+        }
+    }
+
+    fn check_target(&mut self, target: &Expr) {
+        if let Expr::Variable(name) = target {
+            self.check_assignable(name);
+        }
+    }
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Resolver::new()
+    }
+}
+
+impl Visitor<()> for Resolver {
+    fn visit_binary_expr(&mut self, expr: &BinaryExpr) {
+        expr.left.accept(self);
+        expr.right.accept(self);
+    }
+
+    fn visit_grouping_expr(&mut self, expr: &GroupingExpr) {
+        expr.expression.accept(self);
+    }
+
+    fn visit_literal_expr(&mut self, _expr: &LiteralExpr) {}
+
+    fn visit_unary_expr(&mut self, expr: &UnaryExpr) {
+        expr.right.accept(self);
+    }
+
+    fn visit_variable_expr(&mut self, name: &Token) {
+        self.check_initialized(name);
+    }
+
+    fn visit_assign_expr(&mut self, expr: &AssignExpr) {
+        expr.value.accept(self);
+        self.check_assignable(&expr.name);
+    }
+
+    fn visit_destructure_assign_expr(&mut self, expr: &DestructureAssignExpr) {
+        expr.value.accept(self);
+        for name in expr.pattern.bindings() {
+            self.check_assignable(&name);
+        }
+    }
+
+    fn visit_logical_expr(&mut self, expr: &LogicalExpr) {
+        expr.left.accept(self);
+        expr.right.accept(self);
+    }
+
+    fn visit_call_expr(&mut self, expr: &CallExpr) {
+        expr.callee.accept(self);
+        for argument in &expr.arguments {
+            argument.accept(self);
+        }
+        for (_, value) in &expr.named {
+            value.accept(self);
+        }
+    }
+
+    fn visit_get_expr(&mut self, expr: &GetExpr) {
+        expr.object.accept(self);
+    }
+
+    fn visit_list_expr(&mut self, expr: &ListExpr) {
+        for element in &expr.elements {
+            element.accept(self);
+        }
+    }
+
+    fn visit_index_expr(&mut self, expr: &IndexExpr) {
+        expr.object.accept(self);
+        expr.index.accept(self);
+    }
+
+    fn visit_set_index_expr(&mut self, expr: &SetIndexExpr) {
+        // A constant list can still have its elements replaced
+        expr.object.accept(self);
+        expr.index.accept(self);
+        expr.value.accept(self);
+    }
+
+    fn visit_compound_assign_expr(&mut self, expr: &CompoundAssignExpr) {
+        expr.target.accept(self);
+        expr.value.accept(self);
+        self.check_target(&expr.target);
+    }
+
+    fn visit_increment_expr(&mut self, expr: &IncrementExpr) {
+        expr.target.accept(self);
+        self.check_target(&expr.target);
+    }
+}
+
+impl StmtVisitor<()> for Resolver {
+    fn visit_expression_stmt(&mut self, expr: &Expr) {
+        expr.accept(self);
+    }
+
+    fn visit_print_stmt(&mut self, expr: &Expr) {
+        expr.accept(self);
+    }
+
+    fn visit_var_stmt(&mut self, name: &Token, _ty: &Option<Type>, initializer: &Option<Expr>) {
+        if let Some(init) = initializer {
+            init.accept(self);
+        }
+        self.declare(name, false, initializer.is_some());
+    }
+
+    fn visit_const_stmt(&mut self, name: &Token, _ty: &Option<Type>, initializer: &Expr) {
+        initializer.accept(self);
+        self.declare(name, true, true);
+    }
+
+    fn visit_function_stmt(&mut self, name: &Token, params: &Vec<Param>, rest: &Option<Token>, _return_type: &Option<Type>, body: &Vec<Stmt>, generator: bool) {
+        // Declare first so the body can refer to the function recursively
+        self.declare(name, false, true);
+        self.function_depth += 1;
+        let enclosing_generator = std::mem::replace(&mut self.in_generator, generator);
+        self.scopes.push(HashMap::new());
+        // A default can use the parameters before it
+        for param in params {
+            if let Some(default) = &param.default {
+                default.accept(self);
+            }
+            self.declare(&param.name, false, true);
+        }
+        if let Some(rest) = rest {
+            self.declare(rest, false, true);
+        }
+        self.resolve_stmts(body);
+        self.scopes.pop();
+        self.in_generator = enclosing_generator;
+        self.function_depth -= 1;
+    }
+
+    fn visit_return_stmt(&mut self, _keyword: &Token, value: &Option<Expr>) {
+        if let Some(expr) = value {
+            expr.accept(self);
+        }
+    }
+
+    fn visit_yield_stmt(&mut self, keyword: &Token, value: &Option<Expr>) {
+        if !self.in_generator {
+            self.errors.push((keyword.clone(), "Can't use 'yield' outside a generator function.".to_string()));
+        }
+        if let Some(expr) = value {
+            expr.accept(self);
+        }
+    }
+
+    fn visit_block_stmt(&mut self, statements: &Vec<Stmt>) {
+        self.resolve_block(statements);
+    }
+
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Box<Stmt>, else_branch: &Option<Box<Stmt>>) {
+        condition.accept(self);
+        then_branch.accept(self);
+        if let Some(branch) = else_branch {
+            branch.accept(self);
+        }
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Box<Stmt>) {
+        condition.accept(self);
+        body.accept(self);
+    }
+
+    fn visit_for_in_stmt(&mut self, name: &Token, iterable: &Expr, body: &Stmt) {
+        iterable.accept(self);
+        self.scopes.push(HashMap::new());
+        self.declare(name, false, true);
+        body.accept(self);
+        self.scopes.pop();
+    }
+
+    // Each arm gets its own scope for the names its pattern binds. A second
+    // `_` arm is an error; arms after one that matches everything are only
+    // warned about.
+    fn visit_match_stmt(&mut self, _keyword: &Token, subject: &Expr, arms: &[MatchArm]) {
+        subject.accept(self);
+        let mut has_wildcard = false;
+        let mut reachable = true;
+        for arm in arms {
+            let token = arm.pattern.token();
+            let is_wildcard = matches!(arm.pattern, Pattern::Wildcard(_)) && arm.guard.is_none();
+            if is_wildcard && has_wildcard {
+                self.errors.push((token.clone(), "Duplicate wildcard arm in match.".to_string()));
+            } else if !reachable {
+                global_logger().log(LogLevel::Warn, format!("resolver: [line {}] Warning at '{}': Unreachable match arm.", token.line, token.lexeme));
+            }
+            if let Some(alternative) = arm.pattern.mismatched_alternative() {
+                self.errors.push((alternative.token().clone(), "Alternatives in an or-pattern must bind the same names.".to_string()));
+            }
+            has_wildcard |= is_wildcard;
+            if arm.guard.is_none() && arm.pattern.is_irrefutable() {
+                reachable = false;
+            }
+
+            self.scopes.push(HashMap::new());
+            for name in arm.pattern.bindings() {
+                self.declare(&name, false, true);
+            }
+            if let Some(guard) = &arm.guard {
+                guard.accept(self);
+            }
+            arm.body.accept(self);
+            self.scopes.pop();
+        }
+    }
+
+    fn visit_var_pattern_stmt(&mut self, pattern: &Pattern, initializer: &Expr) {
+        initializer.accept(self);
+        for name in pattern.bindings() {
+            self.declare(&name, false, true);
+        }
+    }
+
+    fn visit_throw_stmt(&mut self, _keyword: &Token, value: &Expr) {
+        value.accept(self);
+    }
+
+    fn visit_import_stmt(&mut self, _keyword: &Token, _path: &Token, name: &Token) {
+        self.declare(name, false, true);
+    }
+
+    fn visit_try_stmt(&mut self, body: &[Stmt], catch_name: &Option<Token>, catch_body: Option<&[Stmt]>, finally_body: Option<&[Stmt]>) {
+        self.resolve_block(body);
+        if let Some(catch_stmts) = catch_body {
+            self.scopes.push(HashMap::new());
+            if let Some(name) = catch_name {
+                self.declare(name, false, true);
+            }
+            self.resolve_stmts(catch_stmts);
+            self.scopes.pop();
+        }
+        if let Some(finally_stmts) = finally_body {
+            self.resolve_block(finally_stmts);
+        }
+    }
+}
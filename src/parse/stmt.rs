@@ -1,27 +1,73 @@
 use crate::parse::expr::Expr;
+use crate::parse::pattern::Pattern;
+use crate::parse::types::Type;
 use crate::token::token::Token;
 
+// A function parameter, e.g. `b: number = 2`. The default is evaluated at
+// call time when no argument is given for it.
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: Token,
+    pub ty: Option<Type>,
+    pub default: Option<Expr>,
+}
+
+// One `pattern if guard => body` arm of a match statement
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Stmt,
+}
+
 #[derive(Debug, Clone)]
 pub enum Stmt {
     Expression(Expr),
     Print(Expr),
-    Var { name: Token, initializer: Option<Expr> },
-    Function { name: Token, params: Vec<Token>, body: Vec<Stmt> },
+    // var name: ty = initializer; the annotation and initializer are optional
+    Var { name: Token, ty: Option<Type>, initializer: Option<Expr> },
+    // var [a, b] = initializer; or var {x, y} = initializer;
+    VarPattern { pattern: Pattern, initializer: Expr },
+    // const name = initializer; the binding can't be reassigned
+    Const { name: Token, ty: Option<Type>, initializer: Expr },
+    // fun name(params, ...rest): return_type { body }; `rest` collects extra
+    // arguments. `generator` is set for `fun*`, whose calls return a generator.
+    Function { name: Token, params: Vec<Param>, rest: Option<Token>, return_type: Option<Type>, body: Vec<Stmt>, generator: bool },
     Return { keyword: Token, value: Option<Expr> },
+    // yield value; only inside a generator function
+    Yield { keyword: Token, value: Option<Expr> },
     Block(Vec<Stmt>),
     If { condition: Expr, then_branch: Box<Stmt>, else_branch: Option<Box<Stmt>> },
     While { condition: Expr, body: Box<Stmt> },
+    // for (name in iterable) body; `name` is a fresh binding each iteration
+    ForIn { name: Token, iterable: Expr, body: Box<Stmt> },
+    // match (subject) { arms }; runs the first arm that matches, if any
+    Match { keyword: Token, subject: Expr, arms: Vec<MatchArm> },
+    Throw { keyword: Token, value: Expr },
+    // import "path" as name; `path` is the string token
+    Import { keyword: Token, path: Token, name: Token },
+    // try { body } catch (catch_name) { catch_body } finally { finally_body }
+    // At least one of the catch or finally parts is present.
+    Try { body: Vec<Stmt>, catch_name: Option<Token>, catch_body: Option<Vec<Stmt>>, finally_body: Option<Vec<Stmt>> },
 }
 
 pub trait Visitor<R> {
     fn visit_expression_stmt(&mut self, expr: &Expr) -> R;
     fn visit_print_stmt(&mut self, expr: &Expr) -> R;
-    fn visit_var_stmt(&mut self, name: &Token, initializer: &Option<Expr>) -> R;
-    fn visit_function_stmt(&mut self, name: &Token, params: &Vec<Token>, body: &Vec<Stmt>) -> R;
+    fn visit_var_stmt(&mut self, name: &Token, ty: &Option<Type>, initializer: &Option<Expr>) -> R;
+    fn visit_var_pattern_stmt(&mut self, pattern: &Pattern, initializer: &Expr) -> R;
+    fn visit_const_stmt(&mut self, name: &Token, ty: &Option<Type>, initializer: &Expr) -> R;
+    fn visit_function_stmt(&mut self, name: &Token, params: &Vec<Param>, rest: &Option<Token>, return_type: &Option<Type>, body: &Vec<Stmt>, generator: bool) -> R;
     fn visit_return_stmt(&mut self, keyword: &Token, value: &Option<Expr>) -> R;
+    fn visit_yield_stmt(&mut self, keyword: &Token, value: &Option<Expr>) -> R;
     fn visit_block_stmt(&mut self, statements: &Vec<Stmt>) -> R;
     fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Box<Stmt>, else_branch: &Option<Box<Stmt>>) -> R;
     fn visit_while_stmt(&mut self, condition: &Expr, body: &Box<Stmt>) -> R;
+    fn visit_for_in_stmt(&mut self, name: &Token, iterable: &Expr, body: &Stmt) -> R;
+    fn visit_match_stmt(&mut self, keyword: &Token, subject: &Expr, arms: &[MatchArm]) -> R;
+    fn visit_throw_stmt(&mut self, keyword: &Token, value: &Expr) -> R;
+    fn visit_import_stmt(&mut self, keyword: &Token, path: &Token, name: &Token) -> R;
+    fn visit_try_stmt(&mut self, body: &[Stmt], catch_name: &Option<Token>, catch_body: Option<&[Stmt]>, finally_body: Option<&[Stmt]>) -> R;
 }
 
 impl Stmt {
@@ -29,12 +75,20 @@ impl Stmt {
         match self {
             Stmt::Expression(expr) => visitor.visit_expression_stmt(expr),
             Stmt::Print(expr) => visitor.visit_print_stmt(expr),
-            Stmt::Var { name, initializer } => visitor.visit_var_stmt(name, initializer),
-            Stmt::Function { name, params, body } => visitor.visit_function_stmt(name, params, body),
+            Stmt::Var { name, ty, initializer } => visitor.visit_var_stmt(name, ty, initializer),
+            Stmt::VarPattern { pattern, initializer } => visitor.visit_var_pattern_stmt(pattern, initializer),
+            Stmt::Const { name, ty, initializer } => visitor.visit_const_stmt(name, ty, initializer),
+            Stmt::Function { name, params, rest, return_type, body, generator } => visitor.visit_function_stmt(name, params, rest, return_type, body, *generator),
             Stmt::Return { keyword, value } => visitor.visit_return_stmt(keyword, value),
+            Stmt::Yield { keyword, value } => visitor.visit_yield_stmt(keyword, value),
             Stmt::Block(stmts) => visitor.visit_block_stmt(stmts),
             Stmt::If { condition, then_branch, else_branch } => visitor.visit_if_stmt(condition, then_branch, else_branch),
             Stmt::While { condition, body } => visitor.visit_while_stmt(condition, body),
+            Stmt::ForIn { name, iterable, body } => visitor.visit_for_in_stmt(name, iterable, body),
+            Stmt::Match { keyword, subject, arms } => visitor.visit_match_stmt(keyword, subject, arms),
+            Stmt::Throw { keyword, value } => visitor.visit_throw_stmt(keyword, value),
+            Stmt::Import { keyword, path, name } => visitor.visit_import_stmt(keyword, path, name),
+            Stmt::Try { body, catch_name, catch_body, finally_body } => visitor.visit_try_stmt(body, catch_name, catch_body.as_deref(), finally_body.as_deref()),
         }
     }
 }
@@ -0,0 +1,484 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::parse::expr::{Expr, Visitor, BinaryExpr, GroupingExpr, LiteralExpr, LiteralValue, UnaryExpr, AssignExpr, LogicalExpr, CallExpr, GetExpr, ListExpr, IndexExpr, SetIndexExpr, CompoundAssignExpr, IncrementExpr, DestructureAssignExpr};
+use crate::parse::stmt::{Stmt, Param, MatchArm, Visitor as StmtVisitor};
+use crate::parse::pattern::Pattern;
+use crate::parse::types::Type;
+use crate::token::token::Token;
+use crate::token::token::TokenType;
+
+// The annotated parts of a function declaration, used to check its calls
+struct Signature {
+    name: String,
+    params: Vec<(String, Type)>,
+    returns: Type,
+    generator: bool,
+}
+
+// What the checker knows about a declared name
+struct Binding {
+    ty: Type,
+    // Set for names declared with `fun`
+    signature: Option<Rc<Signature>>,
+}
+
+// The function whose body is being checked
+struct Current {
+    name: String,
+    returns: Type,
+}
+
+// Static type checks run on each statement after the resolver, using the
+// optional annotations on variables, constants, parameters and return
+// types. Anything unannotated (and anything the checker can't work out,
+// like the result of indexing) is Any and passes every check, so code
+// without annotations is never rejected. The interpreter ignores
+// annotations entirely.
+pub struct TypeChecker {
+    // Innermost scope last. The first scope holds globals and persists
+    // between statements, as in the Resolver.
+    scopes: Vec<HashMap<String, Binding>>,
+    errors: Vec<(Token, String)>,
+    current: Option<Current>,
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        TypeChecker { scopes: vec![HashMap::new()], errors: Vec::new(), current: None }
+    }
+
+    // Check one top-level statement and return the type errors found in it
+    pub fn check(&mut self, stmt: &Stmt) -> Vec<(Token, String)> {
+        stmt.accept(self);
+        self.scopes.truncate(1);
+        self.current = None;
+        std::mem::take(&mut self.errors)
+    }
+
+    fn check_stmts(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            stmt.accept(self);
+        }
+    }
+
+    fn check_block(&mut self, statements: &[Stmt]) {
+        self.scopes.push(HashMap::new());
+        self.check_stmts(statements);
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: &Token, ty: Type, signature: Option<Rc<Signature>>) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.lexeme.clone(), Binding { ty, signature });
+        }
+    }
+
+    fn lookup(&self, name: &Token) -> Option<&Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(&name.lexeme))
+    }
+
+    fn mismatch(&mut self, token: &Token, message: String) {
+        self.errors.push((token.clone(), format!("Type mismatch: {}", message)));
+    }
+
+    // Report an operand of a numeric operator that can't be a number.
+    // Operators are only checked when an operand's type comes from an
+    // annotation, so mistakes in unannotated code like `1 + nil` stay
+    // runtime errors.
+    fn expect_number(&mut self, operator: &Token, operands: &[(&Expr, &Type)]) {
+        if !operands.iter().any(|(expr, ty)| is_annotated(expr, ty)) {
+            return;
+        }
+        if let Some((_, bad)) = operands.iter().find(|(_, ty)| !ty.could_be(&Type::Number)) {
+            let message = if operands.len() == 1 {
+                format!("operand of '{}' must be a number, got {}.", operator.lexeme, bad)
+            } else {
+                format!("operands of '{}' must be numbers, got {}.", operator.lexeme, bad)
+            };
+            self.mismatch(operator, message);
+        }
+    }
+
+    // Result of `left operator right`, reporting operands that can't work
+    fn binary_type(&mut self, operator: &Token, left: (&Expr, &Type), right: (&Expr, &Type)) -> Type {
+        let (left_ty, right_ty) = (left.1, right.1);
+        match operator.get_type() {
+            TokenType::Plus => {
+                // Strings concatenate with anything; otherwise both must be numbers
+                if *left_ty == Type::String || *right_ty == Type::String {
+                    Type::String
+                } else if left_ty.could_be(&Type::Number) && right_ty.could_be(&Type::Number) {
+                    if left_ty.could_be(&Type::String) || right_ty.could_be(&Type::String) { Type::Any } else { Type::Number }
+                } else if left_ty.could_be(&Type::String) || right_ty.could_be(&Type::String) {
+                    Type::Any
+                } else {
+                    if is_annotated(left.0, left_ty) || is_annotated(right.0, right_ty) {
+                        self.mismatch(operator, format!("operands of '+' must be numbers or strings, got {} and {}.", left_ty, right_ty));
+                    }
+                    Type::Any
+                }
+            }
+            TokenType::Greater | TokenType::GreaterEqual | TokenType::Less | TokenType::LessEqual
+            | TokenType::EqualEqual | TokenType::BangEqual => Type::Bool,
+            _ => {
+                self.expect_number(operator, &[left, right]);
+                Type::Number
+            }
+        }
+    }
+
+    // Check a value stored into the variable `name`
+    fn check_assignment(&mut self, name: &Token, value: &Type) {
+        let declared = match self.lookup(name) {
+            Some(binding) => binding.ty.clone(),
+            None => return,
+        };
+        if !declared.accepts(value) {
+            self.mismatch(name, format!("can't assign {} to '{}' of type {}.", value, name.lexeme, declared));
+        }
+    }
+
+    // Check the arguments of a call to a function declared with `fun`
+    fn check_arguments(&mut self, signature: &Signature, expr: &CallExpr, positional: &[Type], named: &[Type]) {
+        let by_position = signature.params.iter().zip(positional);
+        let by_name = expr.named.iter().zip(named).filter_map(|((name, _), ty)| {
+            signature.params.iter().find(|(param, _)| *param == name.lexeme).map(|param| (param, ty))
+        });
+        let bad: Vec<String> = by_position.chain(by_name)
+            .filter(|((_, expected), actual)| !expected.accepts(actual))
+            .map(|((param, expected), actual)| format!("argument '{}' of '{}' expects {} but got {}.", param, signature.name, expected, actual))
+            .collect();
+        for message in bad {
+            self.mismatch(&expr.paren, message);
+        }
+    }
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        TypeChecker::new()
+    }
+}
+
+impl Visitor<Type> for TypeChecker {
+    fn visit_binary_expr(&mut self, expr: &BinaryExpr) -> Type {
+        let left = expr.left.accept(self);
+        let right = expr.right.accept(self);
+        self.binary_type(&expr.operator, (&expr.left, &left), (&expr.right, &right))
+    }
+
+    fn visit_grouping_expr(&mut self, expr: &GroupingExpr) -> Type {
+        expr.expression.accept(self)
+    }
+
+    fn visit_literal_expr(&mut self, expr: &LiteralExpr) -> Type {
+        match &expr.value {
+            Some(LiteralValue::Number(_)) | Some(LiteralValue::Int(_)) | Some(LiteralValue::BigInt(_)) => Type::Number,
+            Some(LiteralValue::Str(_)) => Type::String,
+            Some(LiteralValue::Bool(_)) => Type::Bool,
+            None => Type::Nil,
+        }
+    }
+
+    fn visit_unary_expr(&mut self, expr: &UnaryExpr) -> Type {
+        let operand = expr.right.accept(self);
+        match expr.operator.get_type() {
+            TokenType::Bang => Type::Bool,
+            _ => {
+                self.expect_number(&expr.operator, &[(&expr.right, &operand)]);
+                Type::Number
+            }
+        }
+    }
+
+    fn visit_variable_expr(&mut self, name: &Token) -> Type {
+        self.lookup(name).map(|binding| binding.ty.clone()).unwrap_or(Type::Any)
+    }
+
+    fn visit_assign_expr(&mut self, expr: &AssignExpr) -> Type {
+        let value = expr.value.accept(self);
+        self.check_assignment(&expr.name, &value);
+        value
+    }
+
+    // Destructured parts are Any, so only the value itself is checked
+    fn visit_destructure_assign_expr(&mut self, expr: &DestructureAssignExpr) -> Type {
+        expr.value.accept(self)
+    }
+
+    fn visit_logical_expr(&mut self, expr: &LogicalExpr) -> Type {
+        let left = expr.left.accept(self);
+        let right = expr.right.accept(self);
+        if expr.operator.get_type() == TokenType::QuestionQuestion {
+            // `a ?? b` is a's type without nil, or b's when a is nil
+            let left = match left {
+                Type::Optional(inner) => *inner,
+                Type::Nil => return right,
+                other => other,
+            };
+            return if right == Type::Nil || left == right { left } else { Type::Any };
+        }
+        if left == right { left } else { Type::Any }
+    }
+
+    fn visit_call_expr(&mut self, expr: &CallExpr) -> Type {
+        expr.callee.accept(self);
+        let positional: Vec<Type> = expr.arguments.iter().map(|argument| argument.accept(self)).collect();
+        let named: Vec<Type> = expr.named.iter().map(|(_, value)| value.accept(self)).collect();
+        let signature = match expr.callee.as_ref() {
+            Expr::Variable(name) => self.lookup(name).and_then(|binding| binding.signature.clone()),
+            _ => None,
+        };
+        match signature {
+            Some(signature) => {
+                self.check_arguments(&signature, expr, &positional, &named);
+                // A generator's return type describes what its body returns,
+                // not the generator the call produces
+                match signature.returns.clone() {
+                    _ if signature.generator => Type::Any,
+                    // `f?.()` gives nil when f is nil
+                    Type::Any | Type::Nil | Type::Optional(_) => signature.returns.clone(),
+                    returns if expr.optional => Type::Optional(Box::new(returns)),
+                    returns => returns,
+                }
+            }
+            None => Type::Any,
+        }
+    }
+
+    fn visit_get_expr(&mut self, expr: &GetExpr) -> Type {
+        expr.object.accept(self);
+        Type::Any
+    }
+
+    fn visit_list_expr(&mut self, expr: &ListExpr) -> Type {
+        for element in &expr.elements {
+            element.accept(self);
+        }
+        Type::List
+    }
+
+    fn visit_index_expr(&mut self, expr: &IndexExpr) -> Type {
+        expr.object.accept(self);
+        expr.index.accept(self);
+        Type::Any
+    }
+
+    fn visit_set_index_expr(&mut self, expr: &SetIndexExpr) -> Type {
+        expr.object.accept(self);
+        expr.index.accept(self);
+        expr.value.accept(self)
+    }
+
+    fn visit_compound_assign_expr(&mut self, expr: &CompoundAssignExpr) -> Type {
+        let target = expr.target.accept(self);
+        let value = expr.value.accept(self);
+        // `a += b` checks like `a = a + b`, reported at the compound operator
+        let binary_type = match expr.operator.get_type() {
+            TokenType::PlusEqual => TokenType::Plus,
+            TokenType::MinusEqual => TokenType::Minus,
+            TokenType::StarEqual => TokenType::Star,
+            TokenType::SlashEqual => TokenType::Slash,
+            _ => TokenType::Percent,
+        };
+        let operator = Token::new_token(binary_type, expr.operator.lexeme.clone(), None, expr.operator.line);
+        let result = self.binary_type(&operator, (&expr.target, &target), (&expr.value, &value));
+        if let Expr::Variable(name) = expr.target.as_ref() {
+            self.check_assignment(name, &result);
+        }
+        result
+    }
+
+    fn visit_increment_expr(&mut self, expr: &IncrementExpr) -> Type {
+        let target = expr.target.accept(self);
+        self.expect_number(&expr.operator, &[(&expr.target, &target)]);
+        Type::Number
+    }
+}
+
+impl StmtVisitor<()> for TypeChecker {
+    fn visit_expression_stmt(&mut self, expr: &Expr) {
+        expr.accept(self);
+    }
+
+    fn visit_print_stmt(&mut self, expr: &Expr) {
+        expr.accept(self);
+    }
+
+    fn visit_var_stmt(&mut self, name: &Token, ty: &Option<Type>, initializer: &Option<Expr>) {
+        let value = initializer.as_ref().map(|init| init.accept(self));
+        let declared = ty.clone().unwrap_or(Type::Any);
+        if let Some(value) = value.filter(|value| !declared.accepts(value)) {
+            self.mismatch(name, format!("'{}' is declared {} but initialized with {}.", name.lexeme, declared, value));
+        }
+        self.declare(name, declared, None);
+    }
+
+    fn visit_var_pattern_stmt(&mut self, pattern: &Pattern, initializer: &Expr) {
+        initializer.accept(self);
+        for name in pattern.bindings() {
+            self.declare(&name, Type::Any, None);
+        }
+    }
+
+    fn visit_const_stmt(&mut self, name: &Token, ty: &Option<Type>, initializer: &Expr) {
+        self.visit_var_stmt(name, ty, &Some(initializer.clone()));
+    }
+
+    fn visit_function_stmt(&mut self, name: &Token, params: &Vec<Param>, rest: &Option<Token>, return_type: &Option<Type>, body: &Vec<Stmt>, generator: bool) {
+        let returns = return_type.clone().unwrap_or(Type::Any);
+        let signature = Signature {
+            name: name.lexeme.clone(),
+            params: params.iter().map(|param| (param.name.lexeme.clone(), param.ty.clone().unwrap_or(Type::Any))).collect(),
+            returns: returns.clone(),
+            generator,
+        };
+        // Declare first so the body can call the function recursively
+        self.declare(name, Type::Function, Some(Rc::new(signature)));
+
+        let enclosing = self.current.replace(Current { name: name.lexeme.clone(), returns: returns.clone() });
+        self.scopes.push(HashMap::new());
+        for param in params {
+            let declared = param.ty.clone().unwrap_or(Type::Any);
+            if let Some(default) = &param.default {
+                let value = default.accept(self);
+                if !declared.accepts(&value) {
+                    self.mismatch(&param.name, format!("parameter '{}' is declared {} but its default is {}.", param.name.lexeme, declared, value));
+                }
+            }
+            self.declare(&param.name, declared, None);
+        }
+        if let Some(rest) = rest {
+            self.declare(rest, Type::List, None);
+        }
+        self.check_stmts(body);
+        self.scopes.pop();
+        self.current = enclosing;
+
+        // Falling off the end returns nil. Generators return a generator, so
+        // their bodies don't need to return.
+        if !generator && !returns.accepts(&Type::Nil) && !always_returns(body) {
+            self.mismatch(name, format!("'{}' returns {} but can reach the end without returning.", name.lexeme, returns));
+        }
+    }
+
+    fn visit_return_stmt(&mut self, keyword: &Token, value: &Option<Expr>) {
+        let actual = match value {
+            Some(expr) => expr.accept(self),
+            None => Type::Nil,
+        };
+        let bad = self.current.as_ref().filter(|current| !current.returns.accepts(&actual))
+            .map(|current| format!("'{}' returns {} but this returns {}.", current.name, current.returns, actual));
+        if let Some(message) = bad {
+            self.mismatch(keyword, message);
+        }
+    }
+
+    fn visit_yield_stmt(&mut self, _keyword: &Token, value: &Option<Expr>) {
+        if let Some(expr) = value {
+            expr.accept(self);
+        }
+    }
+
+    fn visit_block_stmt(&mut self, statements: &Vec<Stmt>) {
+        self.check_block(statements);
+    }
+
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Box<Stmt>, else_branch: &Option<Box<Stmt>>) {
+        condition.accept(self);
+        then_branch.accept(self);
+        if let Some(branch) = else_branch {
+            branch.accept(self);
+        }
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Box<Stmt>) {
+        condition.accept(self);
+        body.accept(self);
+    }
+
+    fn visit_for_in_stmt(&mut self, name: &Token, iterable: &Expr, body: &Stmt) {
+        iterable.accept(self);
+        self.scopes.push(HashMap::new());
+        self.declare(name, Type::Any, None);
+        body.accept(self);
+        self.scopes.pop();
+    }
+
+    fn visit_match_stmt(&mut self, _keyword: &Token, subject: &Expr, arms: &[MatchArm]) {
+        subject.accept(self);
+        for arm in arms {
+            self.scopes.push(HashMap::new());
+            for name in arm.pattern.bindings() {
+                self.declare(&name, Type::Any, None);
+            }
+            if let Some(guard) = &arm.guard {
+                guard.accept(self);
+            }
+            arm.body.accept(self);
+            self.scopes.pop();
+        }
+    }
+
+    fn visit_throw_stmt(&mut self, _keyword: &Token, value: &Expr) {
+        value.accept(self);
+    }
+
+    fn visit_import_stmt(&mut self, _keyword: &Token, _path: &Token, name: &Token) {
+        self.declare(name, Type::Any, None);
+    }
+
+    fn visit_try_stmt(&mut self, body: &[Stmt], catch_name: &Option<Token>, catch_body: Option<&[Stmt]>, finally_body: Option<&[Stmt]>) {
+        self.check_block(body);
+        if let Some(catch_stmts) = catch_body {
+            self.scopes.push(HashMap::new());
+            if let Some(name) = catch_name {
+                self.declare(name, Type::Any, None);
+            }
+            self.check_stmts(catch_stmts);
+            self.scopes.pop();
+        }
+        if let Some(finally_stmts) = finally_body {
+            self.check_block(finally_stmts);
+        }
+    }
+}
+
+// Whether running the statements always ends in a return or a throw, so
+// control can't fall off the end of a function body
+fn always_returns(statements: &[Stmt]) -> bool {
+    statements.iter().any(stmt_always_returns)
+}
+
+fn stmt_always_returns(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Return { .. } | Stmt::Throw { .. } => true,
+        Stmt::Block(statements) => always_returns(statements),
+        Stmt::If { then_branch, else_branch: Some(else_branch), .. } => {
+            stmt_always_returns(then_branch) && stmt_always_returns(else_branch)
+        }
+        // There is no `break`, so `while (true)` only ends by returning or throwing
+        Stmt::While { condition: Expr::Literal(literal), .. } => matches!(literal.value, Some(LiteralValue::Bool(true))),
+        // A match with no arm taken runs nothing, so one arm has to match everything
+        Stmt::Match { arms, .. } => {
+            arms.iter().any(|arm| arm.guard.is_none() && arm.pattern.is_irrefutable())
+                && arms.iter().all(|arm| stmt_always_returns(&arm.body))
+        }
+        Stmt::Try { body, catch_body, finally_body, .. } => {
+            finally_body.as_deref().is_some_and(always_returns)
+                || (always_returns(body) && catch_body.as_deref().is_none_or(always_returns))
+        }
+        _ => false,
+    }
+}
+
+// Whether an operand's type was written in an annotation: only declared
+// variables and calls to declared functions get a type other than Any
+// without one
+fn is_annotated(expr: &Expr, ty: &Type) -> bool {
+    match expr {
+        Expr::Grouping(group) => is_annotated(&group.expression, ty),
+        Expr::Variable(_) | Expr::Call(_) => *ty != Type::Any,
+        _ => false,
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+
+// A type written in an annotation (`var x: number`) or worked out by the
+// type checker. Annotations are optional; anything unannotated is Any.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    // Unknown or unannotated: the checker lets it through
+    Any,
+    Nil,
+    Number,
+    String,
+    Bool,
+    List,
+    Map,
+    // User functions and natives
+    Function,
+    // `T?`: a T or nil
+    Optional(Box<Type>),
+}
+
+impl Type {
+    // The type an annotation name stands for; `nil` is a keyword and is
+    // handled by the parser
+    pub fn from_name(name: &str) -> Option<Type> {
+        match name {
+            "any" => Some(Type::Any),
+            "number" => Some(Type::Number),
+            "string" => Some(Type::String),
+            "bool" => Some(Type::Bool),
+            "list" => Some(Type::List),
+            "map" => Some(Type::Map),
+            "function" => Some(Type::Function),
+            _ => None,
+        }
+    }
+
+    // Whether a value of type `actual` can be stored where `self` is
+    // expected. Any on either side is accepted.
+    pub fn accepts(&self, actual: &Type) -> bool {
+        match (self, actual) {
+            (Type::Any, _) | (_, Type::Any) => true,
+            (Type::Optional(_), Type::Nil) => true,
+            (Type::Optional(inner), Type::Optional(other)) => inner.accepts(other),
+            (Type::Optional(inner), other) => inner.accepts(other),
+            (expected, actual) => expected == actual,
+        }
+    }
+
+    // Whether a value of this type might be a `wanted` at runtime
+    pub fn could_be(&self, wanted: &Type) -> bool {
+        match self {
+            Type::Any => true,
+            Type::Optional(inner) => *wanted == Type::Nil || inner.could_be(wanted),
+            other => other == wanted,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Any => write!(f, "any"),
+            Type::Nil => write!(f, "nil"),
+            Type::Number => write!(f, "number"),
+            Type::String => write!(f, "string"),
+            Type::Bool => write!(f, "bool"),
+            Type::List => write!(f, "list"),
+            Type::Map => write!(f, "map"),
+            Type::Function => write!(f, "function"),
+            Type::Optional(inner) => write!(f, "{}?", inner),
+        }
+    }
+}
@@ -1 +1,2 @@
+#[allow(clippy::module_inception)]
 pub mod token;
\ No newline at end of file
@@ -4,13 +4,19 @@
 pub enum TokenType {
     // Single-character tokens.
     LeftParen, RightParen, LeftBrace, RightBrace,
-    Comma, Dot, Minus, Plus, Semicolon, Slash, Star,
+    LeftBracket, RightBracket,
+    Comma, Dot, Minus, Plus, Semicolon, Slash, Star, Percent, Colon, Question,
+    Ampersand, Pipe, Caret, Tilde,
 
     // One or two character tokens.
     Bang, BangEqual,
     Equal, EqualEqual,
     Greater, GreaterEqual,
     Less, LessEqual,
+    PlusEqual, MinusEqual, StarEqual, SlashEqual, PercentEqual,
+    PlusPlus, MinusMinus,
+    StarStar, LessLess, GreaterGreater,
+    Ellipsis, FatArrow, QuestionQuestion, QuestionDot,
 
     // Literals.
     Identifier, String, Number,
@@ -18,6 +24,7 @@ pub enum TokenType {
     // Keywords.
     And, Class, Else, False, Fun, For, If, Nil, Or,
     Print, Return, Super, This, True, Var, While,
+    Throw, Try, Catch, Finally, Div, Const, In, Yield, Match, Import, As,
 
     Eof
 }
@@ -1,4 +1,5 @@
-use crate::parse::expr::{Expr, Visitor, BinaryExpr, GroupingExpr, LiteralExpr, UnaryExpr, AssignExpr, LogicalExpr, LiteralValue, CallExpr};
+use crate::parse::expr::{Expr, Visitor, BinaryExpr, GroupingExpr, LiteralExpr, UnaryExpr, AssignExpr, LogicalExpr, LiteralValue, CallExpr, GetExpr, ListExpr, IndexExpr, SetIndexExpr, CompoundAssignExpr, IncrementExpr, DestructureAssignExpr};
+use crate::parse::pattern::Pattern;
 
 // The AstPrinter implements the Visitor trait to produce a string representation of the AST.
 pub struct AstPrinter;
@@ -23,6 +24,43 @@ impl AstPrinter {
         output.push_str(")");
         output
     }
+
+    // Patterns print like the expressions they resemble: names as-is and
+    // list patterns as (list ...)
+    fn pattern(&mut self, pattern: &Pattern) -> String {
+        match pattern {
+            Pattern::Wildcard(token) | Pattern::Binding(token) => token.lexeme.clone(),
+            Pattern::Literal { value, .. } => value.accept(self),
+            Pattern::List { elements, rest, .. } => {
+                let mut output = String::from("(list");
+                for element in elements {
+                    output.push(' ');
+                    output.push_str(&self.pattern(element));
+                }
+                if let Some(rest) = rest {
+                    output.push_str(" ...");
+                    output.push_str(&rest.lexeme);
+                }
+                output.push(')');
+                output
+            }
+            Pattern::Map { entries, .. } => {
+                let mut output = String::from("(map");
+                for (key, element) in entries {
+                    output.push(' ');
+                    output.push_str(&key.lexeme);
+                    output.push(':');
+                    output.push_str(&self.pattern(element));
+                }
+                output.push(')');
+                output
+            }
+            Pattern::Or(alternatives) => {
+                let parts: Vec<String> = alternatives.iter().map(|alternative| self.pattern(alternative)).collect();
+                format!("(| {})", parts.join(" "))
+            }
+        }
+    }
 }
 
 // Implement the Visitor trait, setting the return type R to String.
@@ -39,6 +77,8 @@ impl Visitor<String> for AstPrinter {
     fn visit_literal_expr(&mut self, expr: &LiteralExpr) -> String {
         match &expr.value {
             Some(LiteralValue::Number(n)) => format!("{}", n),
+            Some(LiteralValue::Int(n)) => format!("{}", n),
+            Some(LiteralValue::BigInt(n)) => format!("{}", n),
             Some(LiteralValue::Str(s)) => s.clone(),
             Some(LiteralValue::Bool(b)) => format!("{}", b),
             None => "nil".to_string(),
@@ -83,14 +123,65 @@ impl Visitor<String> for AstPrinter {
     fn visit_call_expr(&mut self, expr: &CallExpr) -> String {
         let mut output = String::new();
         output.push_str("(");
-        output.push_str("call");
+        output.push_str(if expr.optional { "?.call" } else { "call" });
         output.push_str(" ");
         output.push_str(&expr.callee.accept(self));
         for arg in &expr.arguments {
             output.push_str(" ");
             output.push_str(&arg.accept(self));
         }
+        for (name, value) in &expr.named {
+            output.push(' ');
+            output.push_str(&name.lexeme);
+            output.push_str(": ");
+            output.push_str(&value.accept(self));
+        }
         output.push_str(")");
         output
     }
+
+    fn visit_get_expr(&mut self, expr: &GetExpr) -> String {
+        // Represent property access as (. object name)
+        let mut output = String::new();
+        output.push_str(if expr.optional { "(?. " } else { "(. " });
+        output.push_str(&expr.object.accept(self));
+        output.push(' ');
+        output.push_str(&expr.name.lexeme);
+        output.push(')');
+        output
+    }
+
+    fn visit_list_expr(&mut self, expr: &ListExpr) -> String {
+        let mut output = String::new();
+        output.push_str("(list");
+        for element in &expr.elements {
+            output.push(' ');
+            output.push_str(&element.accept(self));
+        }
+        output.push(')');
+        output
+    }
+
+    fn visit_index_expr(&mut self, expr: &IndexExpr) -> String {
+        self.parenthesize("[]", &[&expr.object, &expr.index])
+    }
+
+    fn visit_set_index_expr(&mut self, expr: &SetIndexExpr) -> String {
+        self.parenthesize("[]=", &[&expr.object, &expr.index, &expr.value])
+    }
+
+    fn visit_compound_assign_expr(&mut self, expr: &CompoundAssignExpr) -> String {
+        self.parenthesize(&expr.operator.lexeme, &[&expr.target, &expr.value])
+    }
+
+    fn visit_destructure_assign_expr(&mut self, expr: &DestructureAssignExpr) -> String {
+        let target = self.pattern(&expr.pattern);
+        format!("(= {} {})", target, expr.value.accept(self))
+    }
+
+    fn visit_increment_expr(&mut self, expr: &IncrementExpr) -> String {
+        // Postfix forms are marked so `i++` and `++i` print differently
+        let name = if expr.prefix { expr.operator.lexeme.clone() } else { format!("post{}", expr.operator.lexeme) };
+        self.parenthesize(&name, &[&expr.target])
+    }
 }
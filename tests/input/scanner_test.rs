@@ -117,3 +117,55 @@ fn scanner_unterminated_string() -> TestResult {
 
     Ok(())
 }
+
+#[test]
+fn scanner_numeric_literal_forms() -> TestResult {
+    // Each literal is followed by ';' so the scanner stops at the end of the number
+    let content = "0xFF; 0b1010; 0o17; 1_000_000; 1.5e-3; 2E3; 007; 3.25;";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "test_input4.txt", content)?;
+    let mut scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader initialization failed: {}", e))?;
+
+    let expect = ["255", "10", "15", "1000000", "0.0015", "2000.0", "7", "3.25"];
+    for literal in expect {
+        let tok = scanner.next_token().ok_or_else(|| format!("[FAIL] Expected number {} but got None", literal))?;
+        if tok.get_type() != TokenType::Number { return Err(format!("[FAIL] Expected Number got {:?}", tok.get_type())); }
+        if tok.literal.as_deref() != Some(literal) {
+            return Err(format!("[FAIL] Literal for '{}' expected {} got {:?}", tok.lexeme, literal, tok.literal));
+        }
+        scanner.next_token(); // ';'
+    }
+    if !scanner.take_errors().is_empty() { return Err("[FAIL] Expected no scanner errors".to_string()); }
+
+    Ok(())
+}
+
+#[test]
+fn scanner_malformed_numeric_literals() -> TestResult {
+    let cases = [
+        ("1.;", "Expect digit after '.' in number literal '1.'."),
+        ("0x;", "Expect hex digits after '0x' in number literal."),
+        ("0b102;", "Invalid digit '2' in binary literal '0b102'."),
+        ("1__0;", "Misplaced '_' in number literal '1__0'; it must be between digits."),
+        ("1_;", "Misplaced '_' in number literal '1_'; it must be between digits."),
+        ("1e+;", "Expect digits in exponent of number literal '1e+'."),
+        ("12abc;", "Unexpected character 'a' in number literal '12abc'."),
+        ("2e400;", "Number literal out of range."),
+    ];
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    for (i, (content, message)) in cases.iter().enumerate() {
+        let path = write_temp_file(&temp_dir, &format!("bad_number{}.txt", i), content)?;
+        let mut scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader initialization failed: {}", e))?;
+
+        let tok = scanner.next_token().ok_or_else(|| "[FAIL] Expected a token but got None".to_string())?;
+        if tok.get_type() != TokenType::Number || tok.literal.is_some() {
+            return Err(format!("[FAIL] Expected Number without literal for {}, got {:?}", content, tok));
+        }
+        let errs = scanner.take_errors();
+        if errs.len() != 1 || errs[0] != *message {
+            return Err(format!("[FAIL] For {} expected error {:?}, got {:?}", content, message, errs));
+        }
+    }
+
+    Ok(())
+}
@@ -16,9 +16,14 @@ fn write_temp_file(temp_dir: &tempfile::TempDir, filename: &str, content: &str)
 
 // Helper to run a file through parser+interpreter loop (like main())
 fn run_file_and_return_interpreter(path: &str) -> Result<Interpreter, String> {
+    run_file_with_interpreter(path, Interpreter::new())
+}
+
+// Same as above but with a pre-configured interpreter (e.g. strict mode).
+fn run_file_with_interpreter(path: &str, mut interpreter: Interpreter) -> Result<Interpreter, String> {
+    interpreter.set_script_path(path);
     let scanner = Scanner::new_from_file(path).map_err(|e| format!("Scanner init failed: {}", e))?;
     let mut parser = Parser::new(scanner);
-    let mut interpreter = Interpreter::new();
 
     while !parser.is_at_end() {
         match parser.parse() {
@@ -44,7 +49,7 @@ fn interpret_var_and_lookup() -> TestResult {
     let interp = run_file_and_return_interpreter(&path)?;
     let val = interp.get_global("x");
     match val {
-        Some(Value::Number(n)) => if (n - 99.0).abs() > std::f64::EPSILON { return Err(format!("Expected 99 got {}", n)); },
+        Some(Value::Int(n)) => if n != 99 { return Err(format!("Expected 99 got {}", n)); },
         _ => return Err("Expected numeric global 'x'".to_string()),
     }
     Ok(())
@@ -59,12 +64,28 @@ fn interpret_function_and_call() -> TestResult {
     let interp = run_file_and_return_interpreter(&path)?;
     let val = interp.get_global("r");
     match val {
-        Some(Value::Number(n)) => if (n - 1.0).abs() > std::f64::EPSILON { return Err(format!("Expected 1 got {}", n)); },
+        Some(Value::Int(n)) => if n != 1 { return Err(format!("Expected 1 got {}", n)); },
         _ => return Err("Expected numeric global 'r'".to_string()),
     }
     Ok(())
 }
 
+#[test]
+fn interpret_return_restores_caller_environment() -> TestResult {
+    // `return` unwinds through the function's block; later statements must
+    // run in the caller's scope again
+    let content = "var x = \"global\"; fun f() { var x = \"local\"; { return x; } } var r = f(); var seen = x;\n";
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "return_env.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    match interp.get_global("seen") {
+        Some(Value::Str(s)) if s == "global" => {}
+        other => return Err(format!("Expected the global x after the call, got {:?}", other)),
+    }
+    Ok(())
+}
+
 #[test]
 fn interpret_closure_makecounter() -> TestResult {
     let content = r#"
@@ -90,14 +111,14 @@ var c = makeCounter();
             // call once
             let res1 = rcf.call(&mut interp, &Vec::new()).map_err(|e| format!("Runtime error: {}", e.message))?;
             match res1 {
-                Some(Value::Number(n)) => if (n - 1.0).abs() > std::f64::EPSILON { return Err(format!("Expected 1 got {}", n)); },
+                Some(Value::Int(n)) => if n != 1 { return Err(format!("Expected 1 got {}", n)); },
                 other => return Err(format!("Expected numeric return from c() first call, got {:?}", other)),
             }
 
             // call twice
             let res2 = rcf.call(&mut interp, &Vec::new()).map_err(|e| format!("Runtime error: {}", e.message))?;
             match res2 {
-                Some(Value::Number(n)) => if (n - 2.0).abs() > std::f64::EPSILON { return Err(format!("Expected 2 got {}", n)); },
+                Some(Value::Int(n)) => if n != 2 { return Err(format!("Expected 2 got {}", n)); },
                 other => return Err(format!("Expected numeric return from c() second call, got {:?}", other)),
             }
         }
@@ -131,13 +152,13 @@ fn interpret_assignment_expression_returns_value() -> TestResult {
     let interp = run_file_and_return_interpreter(&path)?;
     let ra = interp.get_global("r");
     match ra {
-        Some(Value::Number(n)) => if (n - 5.0).abs() > std::f64::EPSILON { return Err(format!("Expected 5 got {}", n)); },
+        Some(Value::Int(n)) => if n != 5 { return Err(format!("Expected 5 got {}", n)); },
         _ => return Err("Expected numeric global 'r' from assignment expression".to_string()),
     }
 
     let aa = interp.get_global("a");
     match aa {
-        Some(Value::Number(n)) => if (n - 5.0).abs() > std::f64::EPSILON { return Err(format!("Expected 5 got {}", n)); },
+        Some(Value::Int(n)) => if n != 5 { return Err(format!("Expected 5 got {}", n)); },
         _ => return Err("Expected numeric global 'a' after assignment".to_string()),
     }
 
@@ -173,7 +194,7 @@ var r = fact(5);
     let interp = run_file_and_return_interpreter(&path)?;
     let vr = interp.get_global("r");
     match vr {
-        Some(Value::Number(n)) => if (n - 120.0).abs() > std::f64::EPSILON { return Err(format!("Expected 120 got {}", n)); },
+        Some(Value::Int(n)) => if n != 120 { return Err(format!("Expected 120 got {}", n)); },
         _ => return Err("Expected numeric global 'r' from factorial".to_string()),
     }
 
@@ -246,7 +267,7 @@ var r = outer();
 
     let interp = run_file_and_return_interpreter(&path)?;
     match interp.get_global("r") {
-        Some(Value::Number(n)) => if (n - 5.0).abs() > std::f64::EPSILON { return Err(format!("Expected 5 got {}", n)); },
+        Some(Value::Int(n)) => if n != 5 { return Err(format!("Expected 5 got {}", n)); },
         _ => return Err("Expected numeric global r from outer()".to_string()),
     }
 
@@ -286,7 +307,7 @@ vcall();
 
     let interp = run_file_and_return_interpreter(&path)?;
     match interp.get_global("vcall") {
-        Some(Value::Number(n)) => if (n - 123.0).abs() > std::f64::EPSILON { return Err(format!("Expected 123 got {}", n)); },
+        Some(Value::Int(n)) => if n != 123 { return Err(format!("Expected 123 got {}", n)); },
         _ => return Err("Expected numeric global vcall to remain after non-callable call".to_string()),
     }
 
@@ -378,7 +399,7 @@ var r = inc(4);
 
     let interp = run_file_and_return_interpreter(&path)?;
     match interp.get_global("r") {
-        Some(Value::Number(n)) => if (n - 5.0).abs() > std::f64::EPSILON { return Err(format!("Expected 5 got {}", n)); },
+        Some(Value::Int(n)) => if n != 5 { return Err(format!("Expected 5 got {}", n)); },
         _ => return Err("Expected numeric global r from inc(4)".to_string()),
     }
 
@@ -412,7 +433,7 @@ var r = outer;
 
     let interp = run_file_and_return_interpreter(&path)?;
     match interp.get_global("r") {
-        Some(Value::Number(n)) => if (n - 5.0).abs() > std::f64::EPSILON { return Err(format!("Expected 5 got {}", n)); },
+        Some(Value::Int(n)) => if n != 5 { return Err(format!("Expected 5 got {}", n)); },
         _ => return Err("Expected numeric global r after assigning outer from block".to_string()),
     }
     Ok(())
@@ -450,8 +471,1070 @@ var r = apply(inc, 4);
 
     let interp = run_file_and_return_interpreter(&path)?;
     match interp.get_global("r") {
-        Some(Value::Number(n)) => if (n - 5.0).abs() > std::f64::EPSILON { return Err(format!("Expected 5 got {}", n)); },
+        Some(Value::Int(n)) => if n != 5 { return Err(format!("Expected 5 got {}", n)); },
         _ => return Err("Expected numeric global r from apply(inc,4)".to_string()),
     }
     Ok(())
 }
+
+#[test]
+fn interpret_try_catch_thrown_and_runtime_errors() -> TestResult {
+    let content = r#"
+var thrown;
+try {
+  throw "boom";
+} catch (e) {
+  thrown = e;
+}
+
+var msg;
+var line;
+var kind;
+try {
+  var x = 1 + nil;
+} catch (err) {
+  msg = err.message;
+  line = err.line;
+  kind = type(err);
+}
+
+var rethrown;
+try {
+  try { error("inner"); } catch (e) { throw e; }
+} catch (outer) {
+  rethrown = outer.message;
+}
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "try_catch.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    match interp.get_global("thrown") {
+        Some(Value::Str(s)) => if s != "boom" { return Err(format!("Expected 'boom' got {}", s)); },
+        other => return Err(format!("Expected thrown string, got {:?}", other)),
+    }
+    match interp.get_global("msg") {
+        Some(Value::Str(s)) => if s != "Operands must be two numbers or two strings." { return Err(format!("Unexpected message {}", s)); },
+        other => return Err(format!("Expected caught error message, got {:?}", other)),
+    }
+    match interp.get_global("line") {
+        Some(Value::Int(n)) => if n != 13 { return Err(format!("Expected line 13 got {}", n)); },
+        other => return Err(format!("Expected caught error line, got {:?}", other)),
+    }
+    match interp.get_global("kind") {
+        Some(Value::Str(s)) => if s != "error" { return Err(format!("Expected type 'error' got {}", s)); },
+        other => return Err(format!("Expected error type name, got {:?}", other)),
+    }
+    match interp.get_global("rethrown") {
+        Some(Value::Str(s)) => if s != "inner" { return Err(format!("Expected 'inner' got {}", s)); },
+        other => return Err(format!("Expected rethrown message, got {:?}", other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_finally_runs_on_normal_return_and_throw() -> TestResult {
+    let content = r#"
+var log = "";
+try { log = log + "a"; } finally { log = log + "b"; }
+
+fun early() {
+  try {
+    return "returned";
+  } finally {
+    log = log + "c";
+  }
+  return "not reached";
+}
+var r = early();
+
+fun nested() {
+  try {
+    try { return 1; } finally { log = log + "d"; }
+  } finally {
+    log = log + "e";
+  }
+}
+var n = nested();
+
+try { throw 1; } finally { log = log + "f"; }
+var after_uncaught = true;
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "finally.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    match interp.get_global("log") {
+        Some(Value::Str(s)) => if s != "abcdef" { return Err(format!("Expected finally log 'abcdef' got {}", s)); },
+        other => return Err(format!("Expected string global log, got {:?}", other)),
+    }
+    match interp.get_global("r") {
+        Some(Value::Str(s)) => if s != "returned" { return Err(format!("Expected 'returned' got {}", s)); },
+        other => return Err(format!("Expected return value through finally, got {:?}", other)),
+    }
+    match interp.get_global("n") {
+        Some(Value::Int(n)) => if n != 1 { return Err(format!("Expected 1 got {}", n)); },
+        other => return Err(format!("Expected nested return value, got {:?}", other)),
+    }
+    // An uncaught throw is reported like any runtime error and execution continues.
+    match interp.get_global("after_uncaught") {
+        Some(Value::Bool(true)) => {}
+        other => return Err(format!("Expected script to continue after uncaught throw, got {:?}", other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_compound_assignment_and_increment() -> TestResult {
+    let content = r#"
+var a = 10;
+a += 5;
+a -= 3;
+a *= 2;
+a /= 4;
+var m = 17;
+m %= 5;
+var s = "ab";
+s += "c";
+
+var i = 0;
+var post = i++;
+var pre = ++i;
+var down = i--;
+var minus_negated = 1--1;
+var negated_twice = --1;
+
+var calls = 0;
+fun idx() { calls = calls + 1; return 1; }
+var xs = [1, 2, 3];
+xs[idx()] += 10;
+xs[idx()]++;
+xs[0] = "first";
+
+var bad = 1;
+bad = (bad += "x") * 2;
+var after_bad = true;
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "compound.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    let expect_num = |name: &str, expected: f64| -> TestResult {
+        match interp.get_global(name) {
+            Some(v) if v.as_f64().is_some() => {
+                let n = v.as_f64().unwrap();
+                if (n - expected).abs() > f64::EPSILON { Err(format!("Expected {} = {} got {}", name, expected, n)) } else { Ok(()) }
+            }
+            other => Err(format!("Expected number global {}, got {:?}", name, other)),
+        }
+    };
+    expect_num("a", 6.0)?;
+    expect_num("m", 2.0)?;
+    expect_num("post", 0.0)?;
+    expect_num("pre", 2.0)?;
+    expect_num("down", 2.0)?;
+    expect_num("i", 1.0)?;
+    // `--` next to something that isn't assignable is two minus signs
+    expect_num("minus_negated", 2.0)?;
+    expect_num("negated_twice", 1.0)?;
+    // The index expression is evaluated once per compound assignment/increment
+    expect_num("calls", 2.0)?;
+    match interp.get_global("s") {
+        Some(Value::Str(s)) => if s != "abc" { return Err(format!("Expected 'abc' got {}", s)); },
+        other => return Err(format!("Expected string global s, got {:?}", other)),
+    }
+    if interp.stringify(&interp.get_global("xs")) != "[\"first\", 13, 3]" {
+        return Err(format!("Unexpected list {}", interp.stringify(&interp.get_global("xs"))));
+    }
+    // "1 + x" is string concatenation, so the later `* 2` fails and bad keeps the compound result
+    match interp.get_global("bad") {
+        Some(Value::Str(s)) => if s != "1x" { return Err(format!("Expected '1x' got {}", s)); },
+        other => return Err(format!("Expected string global bad, got {:?}", other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_self_referencing_list_prints_without_recursing() -> TestResult {
+    let content = "var xs = [1];\nxs[0] = xs;\nvar text = str(xs);\n";
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "cycle.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    match interp.get_global("text") {
+        Some(Value::Str(s)) => if s != "[[...]]" { return Err(format!("Expected '[[...]]' got {}", s)); },
+        other => return Err(format!("Expected string global text, got {:?}", other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_modulo_power_div_and_bitwise_operators() -> TestResult {
+    let content = r#"
+var rem = 7 % 3;
+var neg_rem = -7 % 3;
+var pow = 2 ** 10;
+var right_assoc = 2 ** 3 ** 2;
+var neg_pow = -2 ** 2;
+var inv = 2 ** -1;
+var floor_div = 7 div 2;
+var neg_floor_div = -7 div 2;
+var rem_chain = 17 % 5 % 3;
+var div_chain = 100 div 10 div 2;
+var band = 12 & 10;
+var bor = 12 | 3;
+var bxor = 6 ^ 3;
+var bnot = ~5;
+var shl = 1 << 4;
+var shr = 256 >> 2;
+var shl_over = str(1 << 63);
+var shl_lost = str(3 << 62);
+var shl_min = -1 << 63;
+var masked = 5 & 1 == 1;
+var mixed = 1 | 2 + 4;
+
+var frac = 1.5 & 1;
+var bad_shift = 1 << 64;
+var not_num = ~"a";
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "operators.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    let expect_num = |name: &str, expected: f64| -> TestResult {
+        match interp.get_global(name) {
+            Some(v) if v.as_f64().is_some() => {
+                let n = v.as_f64().unwrap();
+                if (n - expected).abs() > f64::EPSILON { Err(format!("Expected {} = {} got {}", name, expected, n)) } else { Ok(()) }
+            }
+            other => Err(format!("Expected number global {}, got {:?}", name, other)),
+        }
+    };
+    expect_num("rem", 1.0)?;
+    expect_num("neg_rem", -1.0)?;
+    expect_num("pow", 1024.0)?;
+    expect_num("right_assoc", 512.0)?;
+    expect_num("neg_pow", -4.0)?;
+    expect_num("inv", 0.5)?;
+    expect_num("floor_div", 3.0)?;
+    expect_num("neg_floor_div", -4.0)?;
+    // % and div associate to the left
+    expect_num("rem_chain", 2.0)?;
+    expect_num("div_chain", 5.0)?;
+    expect_num("band", 8.0)?;
+    expect_num("bor", 15.0)?;
+    expect_num("bxor", 5.0)?;
+    expect_num("bnot", -6.0)?;
+    expect_num("shl", 16.0)?;
+    expect_num("shr", 64.0)?;
+    // Bits shifted past the i64 promote to a big integer, like overflowing `*`
+    for (name, expected) in [("shl_over", "9223372036854775808"), ("shl_lost", "13835058055282163712")] {
+        match interp.get_global(name) {
+            Some(Value::Str(s)) if s == expected => {}
+            other => return Err(format!("Expected {} = {}, got {:?}", name, expected, other)),
+        }
+    }
+    match interp.get_global("shl_min") {
+        Some(Value::Int(i64::MIN)) => {}
+        other => return Err(format!("Expected shl_min to stay an int, got {:?}", other)),
+    }
+    expect_num("mixed", 7.0)?;
+    match interp.get_global("masked") {
+        Some(Value::Bool(true)) => {}
+        other => return Err(format!("Expected masked to be true, got {:?}", other)),
+    }
+    // Non-integral operands are runtime errors, so these stay unset
+    for name in ["frac", "bad_shift", "not_num"] {
+        match interp.get_global(name) {
+            None | Some(Value::Nil) => {}
+            other => return Err(format!("Expected {} to be unset after error, got {:?}", name, other)),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_int_and_float_promotion() -> TestResult {
+    let content = r#"
+var big = 9007199254740993 + 0;
+var ratio = 10 / 4;
+var whole = 10 / 2;
+var quotient = 10 div 3;
+var mixed = 1 + 0.5;
+var pow = 2 ** 62;
+var frac_pow = 2 ** -2;
+var same = 1 == 1.0;
+var not_same = 1 == 1.5;
+var kind = type(3);
+var text = str(2.0) + " " + str(7 div 2);
+var counter = 0;
+counter++;
+
+var overflow = 9223372036854775807 + 1;
+var neg_overflow = -9223372036854775807 - 2;
+var zero_div = 1 div 0;
+var zero_rem = 1 % 0;
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "ints.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    let expect_int = |name: &str, expected: i64| -> TestResult {
+        match interp.get_global(name) {
+            Some(Value::Int(n)) => if n != expected { Err(format!("Expected {} = {} got {}", name, expected, n)) } else { Ok(()) },
+            other => Err(format!("Expected int global {}, got {:?}", name, other)),
+        }
+    };
+    let expect_float = |name: &str, expected: f64| -> TestResult {
+        match interp.get_global(name) {
+            Some(Value::Number(n)) => if (n - expected).abs() > f64::EPSILON { Err(format!("Expected {} = {} got {}", name, expected, n)) } else { Ok(()) },
+            other => Err(format!("Expected float global {}, got {:?}", name, other)),
+        }
+    };
+    // Integers above 2^53 stay exact
+    expect_int("big", 9007199254740993)?;
+    expect_float("ratio", 2.5)?;
+    expect_float("whole", 5.0)?;
+    expect_int("quotient", 3)?;
+    expect_float("mixed", 1.5)?;
+    expect_int("pow", 1 << 62)?;
+    expect_float("frac_pow", 0.25)?;
+    expect_int("counter", 1)?;
+    match (interp.get_global("same"), interp.get_global("not_same")) {
+        (Some(Value::Bool(true)), Some(Value::Bool(false))) => {}
+        other => return Err(format!("Expected 1 == 1.0 and 1 != 1.5, got {:?}", other)),
+    }
+    match interp.get_global("kind") {
+        Some(Value::Str(s)) => if s != "number" { return Err(format!("Expected type number got {}", s)); },
+        other => return Err(format!("Expected string global kind, got {:?}", other)),
+    }
+    match interp.get_global("text") {
+        Some(Value::Str(s)) => if s != "2 3" { return Err(format!("Expected '2 3' got {}", s)); },
+        other => return Err(format!("Expected string global text, got {:?}", other)),
+    }
+    // Overflow promotes to a big integer instead of failing
+    for name in ["overflow", "neg_overflow"] {
+        match interp.get_global(name) {
+            Some(Value::BigInt(_)) => {}
+            other => return Err(format!("Expected {} to be a big integer, got {:?}", name, other)),
+        }
+    }
+    // Integer division by zero is a runtime error
+    for name in ["zero_div", "zero_rem"] {
+        if interp.get_global(name).is_some() {
+            return Err(format!("Expected {} to be unset after error", name));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_big_integer_promotion() -> TestResult {
+    let content = r#"
+fun fact(n) {
+  if (n <= 1) return 1;
+  return n * fact(n - 1);
+}
+var f50 = str(fact(50));
+var max = 9223372036854775807;
+var promoted = max + 1;
+var back = promoted - 1;
+var big_literal = 123456789012345678901234567890;
+var big_div = str(-(2 ** 100) div 7);
+var big_rem = (2 ** 100) % 7;
+var compare = 2 ** 100 > max;
+var huge = 2 ** 10000000;
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "bigint.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    match interp.get_global("f50") {
+        Some(Value::Str(s)) => if s != "30414093201713378043612608166064768844377641568960512000000000000" { return Err(format!("Unexpected fact(50) {}", s)); },
+        other => return Err(format!("Expected string global f50, got {:?}", other)),
+    }
+    match interp.get_global("promoted") {
+        Some(Value::BigInt(n)) => if n.to_string() != "9223372036854775808" { return Err(format!("Unexpected promoted value {}", n)); },
+        other => return Err(format!("Expected big integer, got {:?}", other)),
+    }
+    // Results that fit in an i64 again become plain integers
+    match interp.get_global("back") {
+        Some(Value::Int(n)) => if n != i64::MAX { return Err(format!("Expected i64::MAX got {}", n)); },
+        other => return Err(format!("Expected int global back, got {:?}", other)),
+    }
+    if interp.stringify(&interp.get_global("big_literal")) != "123456789012345678901234567890" {
+        return Err("Big literal did not round-trip".to_string());
+    }
+    match interp.get_global("big_div") {
+        Some(Value::Str(s)) => if s != "-181092942889747057356671886483" { return Err(format!("Unexpected floor division {}", s)); },
+        other => return Err(format!("Expected string global big_div, got {:?}", other)),
+    }
+    match interp.get_global("big_rem") {
+        Some(Value::Int(2)) => {}
+        other => return Err(format!("Expected remainder 2, got {:?}", other)),
+    }
+    match interp.get_global("compare") {
+        Some(Value::Bool(true)) => {}
+        other => return Err(format!("Expected 2**100 > max, got {:?}", other)),
+    }
+    if interp.get_global("huge").is_some() {
+        return Err("Expected oversized power to be a runtime error".to_string());
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_string_and_mixed_type_comparisons() -> TestResult {
+    let content = r#"
+var s1 = "apple" < "banana";
+var s2 = "b" > "abc";
+var s3 = "abc" <= "abc";
+var prefix = "ab" < "abc";
+var mixed = 1 < "1";
+var nil_first = nil < false;
+var big_vs_float = 9007199254740993 > 9007199254740992.0;
+var lists = [1, 2] < [1, 3];
+var nan_greatest = 0 / 0 > 1000000;
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "string_cmp.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    for name in ["s1", "s2", "s3", "prefix", "mixed", "nil_first", "big_vs_float", "lists", "nan_greatest"] {
+        match interp.get_global(name) {
+            Some(Value::Bool(true)) => {}
+            other => return Err(format!("Expected {} to be true, got {:?}", name, other)),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_const_reassignment_fails_at_runtime() -> TestResult {
+    // `bump` is declared before LIMIT exists, so only the runtime check sees it
+    let content = r#"
+fun bump() { LIMIT = LIMIT + 1; }
+const LIMIT = 10;
+var caught;
+try { bump(); } catch (e) { caught = e.message; }
+const items = [1];
+items[0] = 2;
+var first = items[0];
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "const.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    match interp.get_global("LIMIT") {
+        Some(Value::Int(10)) => {}
+        other => return Err(format!("Expected LIMIT to stay 10, got {:?}", other)),
+    }
+    match interp.get_global("caught") {
+        Some(Value::Str(s)) if s == "Cannot assign to constant 'LIMIT'." => {}
+        other => return Err(format!("Expected constant assignment error, got {:?}", other)),
+    }
+    // The binding is constant, not the list it refers to
+    match interp.get_global("first") {
+        Some(Value::Int(2)) => {}
+        other => return Err(format!("Expected first to be 2, got {:?}", other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_strict_mode_rejects_uninitialized_reads() -> TestResult {
+    let content = r#"
+var x;
+var lenient_copy = x;
+var y;
+y = 5;
+var assigned = y;
+fun nothing() { return; }
+var result = nothing();
+var copy_result = result;
+var caught;
+try { var z; z += 1; } catch (e) { caught = e.message; }
+var copy_x = x;
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "strict.txt", content)?;
+
+    // Lenient default: reading x yields nothing, no error
+    let lenient = run_file_and_return_interpreter(&path)?;
+    match lenient.get_global("copy_x") {
+        None => {}
+        other => return Err(format!("Expected copy_x to be uninitialized in lenient mode, got {:?}", other)),
+    }
+
+    let mut interpreter = Interpreter::new();
+    interpreter.set_strict(true);
+    let strict = run_file_with_interpreter(&path, interpreter)?;
+    match strict.get_global("assigned") {
+        Some(Value::Int(5)) => {}
+        other => return Err(format!("Expected assigned to be 5, got {:?}", other)),
+    }
+    // A bare return is a real nil, not an uninitialized value
+    match strict.get_global("copy_result") {
+        Some(Value::Nil) => {}
+        other => return Err(format!("Expected copy_result to be nil, got {:?}", other)),
+    }
+    match strict.get_global("caught") {
+        Some(Value::Str(s)) if s == "Variable 'z' used before initialization." => {}
+        other => return Err(format!("Expected uninitialized error, got {:?}", other)),
+    }
+    // The failed read means the declaration never completed
+    match strict.get_global("copy_x") {
+        None => {}
+        other => return Err(format!("Expected copy_x to stay unset, got {:?}", other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_default_rest_and_named_arguments() -> TestResult {
+    let content = r#"
+var base = 10;
+fun f(a, b = a + base, ...rest) { return [a, b, rest]; }
+var one = f(1);
+var two = f(1, 2, 3, 4);
+var named = f(b: 5, a: 6);
+base = 100;
+var late = f(1);
+var missing;
+try { f(b: 1); } catch (e) { missing = e.message; }
+var unknown;
+try { f(1, c: 2); } catch (e) { unknown = e.message; }
+var twice;
+try { f(1, a: 2); } catch (e) { twice = e.message; }
+fun g(a, b = 2) { }
+var arity;
+try { g(); } catch (e) { arity = e.message; }
+fun h(a, ...rest) { }
+var at_least;
+try { h(); } catch (e) { at_least = e.message; }
+var exact;
+try { len(); } catch (e) { exact = e.message; }
+var native;
+try { len(x: [1]); } catch (e) { native = e.message; }
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "defaults.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    let expect_list = |name: &str, expected: &str| -> TestResult {
+        match interp.get_global(name) {
+            Some(v) => {
+                let shown = interp.stringify(&Some(v));
+                if shown != expected { return Err(format!("Expected {} to be {} got {}", name, expected, shown)); }
+                Ok(())
+            }
+            None => Err(format!("Expected {} to be set", name)),
+        }
+    };
+    expect_list("one", "[1, 11, []]")?;
+    expect_list("two", "[1, 2, [3, 4]]")?;
+    expect_list("named", "[6, 5, []]")?;
+    // Defaults are evaluated at call time
+    expect_list("late", "[1, 101, []]")?;
+
+    for (name, expected) in [
+        ("missing", "Missing argument 'a'."),
+        ("unknown", "Unexpected argument 'c'."),
+        ("twice", "Argument 'a' was given more than once."),
+        ("arity", "Expected 1 to 2 arguments but got 0."),
+        ("at_least", "Expected at least 1 argument but got 0."),
+        ("exact", "Expected 1 argument but got 0."),
+        ("native", "Native functions don't take named arguments."),
+    ] {
+        match interp.get_global(name) {
+            Some(Value::Str(s)) if s == expected => {}
+            other => return Err(format!("Expected {} to be {:?}, got {:?}", name, expected, other)),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_for_in_over_lists_maps_strings_and_ranges() -> TestResult {
+    let content = r#"
+var total = 0;
+for (x in [1, 2, 3]) total += x;
+var m = json_parse("{}");
+m["b"] = 1;
+m["a"] = 2;
+var keys = "";
+for (var k in m) keys += k;
+var chars = "";
+for (c in "héy") chars = c + chars;
+var down = "";
+for (i in range(10, 0, -3)) down += str(i) + " ";
+var empty = 0;
+for (i in range(5, 0)) empty += 1;
+var kind = type(range(3));
+var fs = [nil, nil, nil];
+for (i in range(3)) { fun f() { return i; } fs[i] = f; }
+var captured = fs[0]() * 100 + fs[1]() * 10 + fs[2]();
+var bad;
+try { for (x in 5) print x; } catch (e) { bad = e.message; }
+var zero_step;
+try { range(0, 1, 0); } catch (e) { zero_step = e.message; }
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "for_in.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    match interp.get_global("total") {
+        Some(Value::Int(6)) => {}
+        other => return Err(format!("Expected total 6, got {:?}", other)),
+    }
+    // Each iteration binds a fresh `i`, so the closures see 0, 1 and 2
+    match interp.get_global("captured") {
+        Some(Value::Int(12)) => {}
+        other => return Err(format!("Expected captured 12, got {:?}", other)),
+    }
+    match interp.get_global("empty") {
+        Some(Value::Int(0)) => {}
+        other => return Err(format!("Expected empty range, got {:?}", other)),
+    }
+    for (name, expected) in [
+        ("keys", "ab"),
+        ("chars", "yéh"),
+        ("down", "10 7 4 1 "),
+        ("kind", "range"),
+        ("bad", "Can only iterate over lists, maps, strings, generators and iterables."),
+        ("zero_step", "range: step must not be zero."),
+    ] {
+        match interp.get_global(name) {
+            Some(Value::Str(s)) if s == expected => {}
+            other => return Err(format!("Expected {} to be {:?}, got {:?}", name, expected, other)),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_generators_resume_after_yield() -> TestResult {
+    let content = r#"
+fun* count(n) {
+  var i = 0;
+  while (i < n) { yield i; i += 1; }
+  return "end";
+}
+var g = count(2);
+var kind = type(g);
+var steps = "";
+for (i in range(4)) { var r = g.next(); steps += str(r.value) + ":" + str(r.done) + " "; }
+var total = 0;
+for (x in count(4)) total += x;
+fun* guarded() {
+  try { yield 1; throw "boom"; } catch (e) { yield "caught " + e; } finally { log += "cleanup"; }
+  for (c in "ab") { if (c == "b") return; yield c; }
+}
+var log = "";
+var seen = "";
+for (x in guarded()) seen += str(x) + ",";
+fun* broken() { yield 1; throw "bad"; }
+var b = broken();
+b.next();
+var thrown;
+try { b.next(); } catch (e) { thrown = e; }
+var after_error = b.next().done;
+fun* reentrant() { yield again.next(); }
+var again = reentrant();
+var running;
+try { again.next(); } catch (e) { running = e.message; }
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "generators.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    match interp.get_global("total") {
+        Some(Value::Int(6)) => {}
+        other => return Err(format!("Expected total 6, got {:?}", other)),
+    }
+    // An error ends the generator; later calls report done
+    match interp.get_global("after_error") {
+        Some(Value::Bool(true)) => {}
+        other => return Err(format!("Expected finished generator, got {:?}", other)),
+    }
+    for (name, expected) in [
+        ("kind", "generator"),
+        ("steps", "0:false 1:false end:true nil:true "),
+        ("seen", "1,caught boom,a,"),
+        ("log", "cleanup"),
+        ("thrown", "bad"),
+        ("running", "Generator 'reentrant' is already running."),
+    ] {
+        match interp.get_global(name) {
+            Some(Value::Str(s)) if s == expected => {}
+            other => return Err(format!("Expected {} to be {:?}, got {:?}", name, expected, other)),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_match_patterns_and_guards() -> TestResult {
+    let content = r#"
+fun describe(v) {
+  var out = "none";
+  match (v) {
+    0 | 1.0 => out = "small";
+    -1 => out = "minus one";
+    "a" | "b" => out = "letter";
+    [] => out = "empty";
+    [x, 2] => out = "pair ending in 2 after " + str(x);
+    [first, ...rest] => out = str(first) + " then " + str(rest);
+    n if n > 3 => out = "big " + str(n);
+    nil => out = "nil";
+  }
+  return out;
+}
+var results = "";
+for (v in [0, 1, -1, "b", [], [5, 2], [1, 2, 3], 10, nil, 2]) results += describe(v) + "; ";
+var x = "outer";
+match ([1]) { [x] => { } }
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "match.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    let expected = "small; small; minus one; letter; empty; pair ending in 2 after 5; 1 then [2, 3]; big 10; nil; none; ";
+    match interp.get_global("results") {
+        Some(Value::Str(s)) if s == expected => {}
+        other => return Err(format!("Expected {:?}, got {:?}", expected, other)),
+    }
+    // Bindings live in the arm's own scope
+    match interp.get_global("x") {
+        Some(Value::Str(s)) if s == "outer" => {}
+        other => return Err(format!("Expected x to stay \"outer\", got {:?}", other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_destructuring_declarations_and_swap() -> TestResult {
+    let content = r#"
+var [a, b] = [1, 2];
+[a, b] = [b, a];
+var point = json_parse("{}");
+point["x"] = 3;
+point["y"] = [4, 5];
+var {x, y: [y1, _]} = point;
+var [head, ...tail] = [1, 2, 3];
+var short;
+try { var [p, q] = [1]; } catch (e) { short = e.message; }
+var missing;
+try { var {z} = point; } catch (e) { missing = e.message; }
+var wrong;
+try { [a, b] = 5; } catch (e) { wrong = e.message; }
+var one;
+try { var [only] = [1, 2]; } catch (e) { one = e.message; }
+var undefined_target;
+try { [a, nowhere] = [7, 8]; } catch (e) { undefined_target = e.message; }
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "destructure.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    // A failed destructuring assignment, including one with an undefined
+    // target, leaves the swapped values alone
+    for (name, expected) in [("a", 2), ("b", 1), ("x", 3), ("y1", 4), ("head", 1)] {
+        match interp.get_global(name) {
+            Some(Value::Int(n)) if n == expected => {}
+            other => return Err(format!("Expected {} to be {}, got {:?}", name, expected, other)),
+        }
+    }
+    match interp.get_global("tail") {
+        Some(Value::List(items)) if items.borrow().len() == 2 => {}
+        other => return Err(format!("Expected tail [2, 3], got {:?}", other)),
+    }
+    for (name, expected) in [
+        ("short", "Expected 2 elements but got 1."),
+        ("missing", "Missing key 'z'."),
+        ("wrong", "Expected a list to destructure, got number."),
+        ("one", "Expected 1 element but got 2."),
+        ("undefined_target", "Undefined variable 'nowhere'."),
+    ] {
+        match interp.get_global(name) {
+            Some(Value::Str(s)) if s == expected => {}
+            other => return Err(format!("Expected {} to be {:?}, got {:?}", name, expected, other)),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_import_modules_cached_and_cycles() -> TestResult {
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    fs::create_dir(td.path().join("lib")).map_err(|e| format!("Mkdir failed: {}", e))?;
+    // Paths inside a module resolve relative to the module's own file. The
+    // module can't see or change the importer's variables.
+    let util = r#"import "helper.lox" as helper;
+var box = [0];
+var base = helper.base;
+fun double(x) { return x * 2; }
+var seen;
+try { seen = secret; } catch (e) { seen = e.message; }
+try { secret = "clobbered"; } catch (e) { }
+"#;
+    write_temp_file(&td, "lib/util.lox", util)?;
+    write_temp_file(&td, "lib/helper.lox", "var base = 10;\n")?;
+    write_temp_file(&td, "lib/a.lox", "import \"b.lox\" as b;\n")?;
+    write_temp_file(&td, "lib/b.lox", "import \"a.lox\" as a;\n")?;
+    write_temp_file(&td, "lib/outer.lox", "import \"inner.lox\" as inner;\n")?;
+    write_temp_file(&td, "lib/inner.lox", "var broken = 1 + nil;\n")?;
+    let content = r#"
+var secret = "mine";
+import "lib/util.lox" as util;
+import "./lib/../lib/util.lox" as same;
+util.box[0] = 1;
+var shared = same.box[0];
+var result = util.double(util.base);
+var kind = type(util);
+var seen = util.seen;
+var cycle;
+try { import "lib/a.lox" as a; } catch (e) { cycle = e.message; }
+var nested;
+try { import "lib/outer.lox" as outer; } catch (e) { nested = e.message; }
+var missing;
+try { import "lib/none.lox" as none; } catch (e) { missing = e.message; }
+"#;
+    let path = write_temp_file(&td, "main.lox", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    // The second import of util.lox comes from the cache, so both names
+    // share the module's list
+    match interp.get_global("shared") {
+        Some(Value::Int(1)) => {}
+        other => return Err(format!("Expected module to run once, got {:?}", other)),
+    }
+    match interp.get_global("result") {
+        Some(Value::Int(20)) => {}
+        other => return Err(format!("Expected result 20, got {:?}", other)),
+    }
+    for (name, expected) in [
+        ("kind", "namespace"),
+        ("secret", "mine"),
+        ("seen", "Undefined variable 'secret'."),
+        ("cycle", "Import cycle: a.lox -> b.lox -> a.lox."),
+        ("nested", "Operands must be two numbers or two strings. (in module 'inner.lox')"),
+    ] {
+        match interp.get_global(name) {
+            Some(Value::Str(s)) if s == expected => {}
+            other => return Err(format!("Expected {} to be {:?}, got {:?}", name, expected, other)),
+        }
+    }
+    match interp.get_global("missing") {
+        Some(Value::Str(s)) if s.starts_with("Can't import 'lib/none.lox'") => {}
+        other => return Err(format!("Expected missing module error, got {:?}", other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_type_annotations_checked_before_running() -> TestResult {
+    // Annotated code runs as usual; statements the checker rejects never run
+    let content = r#"
+var total: number = 0;
+fun add(a: number, b: number = 1): number { return a + b; }
+total = add(total, 2);
+var label: string? = nil;
+label = "sum " + total;
+total = "oops";
+fun wrong(): string { total = 100; return 1; }
+var unannotated = "anything";
+unannotated = 5;
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "types.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    match interp.get_global("total") {
+        Some(Value::Int(2)) => {}
+        other => return Err(format!("Expected total to stay 2, got {:?}", other)),
+    }
+    match interp.get_global("label") {
+        Some(Value::Str(s)) if s == "sum 2" => {}
+        other => return Err(format!("Expected label 'sum 2', got {:?}", other)),
+    }
+    if interp.get_global("wrong").is_some() { return Err("Expected 'wrong' to be rejected".to_string()); }
+    match interp.get_global("unannotated") {
+        Some(Value::Int(5)) => {}
+        other => return Err(format!("Expected unannotated to be 5, got {:?}", other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_nil_coalescing_and_optional_chaining() -> TestResult {
+    let content = r#"
+var calls = 0;
+fun count() { calls = calls + 1; return calls; }
+var missing;
+var fallback = missing ?? "default";
+var kept = false ?? count();
+var chained = nil ?? nil ?? 3;
+var q = chr(34);
+var config = json_parse("{" + q + "db" + q + ": {" + q + "port" + q + ": 5432}}");
+var port = config?.db?.port;
+var no_port = missing?.db?.port;
+var skipped = missing?.(count());
+var called = count?.();
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "nil_safe.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    match interp.get_global("fallback") {
+        Some(Value::Str(s)) if s == "default" => {}
+        other => return Err(format!("Expected default, got {:?}", other)),
+    }
+    // Only nil falls through, and the right side isn't evaluated otherwise
+    match interp.get_global("kept") {
+        Some(Value::Bool(false)) => {}
+        other => return Err(format!("Expected false to be kept, got {:?}", other)),
+    }
+    match interp.get_global("chained") {
+        Some(Value::Int(3)) => {}
+        other => return Err(format!("Expected 3, got {:?}", other)),
+    }
+    match interp.get_global("port") {
+        Some(Value::Int(5432)) => {}
+        other => return Err(format!("Expected port 5432, got {:?}", other)),
+    }
+    for name in ["no_port", "skipped"] {
+        match interp.get_global(name) {
+            Some(Value::Nil) => {}
+            other => return Err(format!("Expected {} to be nil, got {:?}", name, other)),
+        }
+    }
+    // The skipped call didn't evaluate its argument
+    match interp.get_global("called") {
+        Some(Value::Int(1)) => {}
+        other => return Err(format!("Expected one call, got {:?}", other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_optional_chaining_skips_rest_of_chain() -> TestResult {
+    // Once a `?.` sees nil the links after it are skipped, but parentheses
+    // end the chain
+    let content = r#"
+var calls = 0;
+fun count() { calls = calls + 1; return calls; }
+var a = nil;
+var field = a?.b.c;
+var method = a?.b(count());
+var indexed = a?.b[0].c;
+var grouped;
+try { grouped = (a?.b).c; } catch (e) { grouped = e.message; }
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "optional_chain.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    for name in ["field", "method", "indexed"] {
+        match interp.get_global(name) {
+            Some(Value::Nil) => {}
+            other => return Err(format!("Expected {} to be nil, got {:?}", name, other)),
+        }
+    }
+    match interp.get_global("calls") {
+        Some(Value::Int(0)) => {}
+        other => return Err(format!("Expected the skipped call's argument not to run, got {:?}", other)),
+    }
+    match interp.get_global("grouped") {
+        Some(Value::Str(s)) if s == "Only namespaces, maps, errors and generators have properties." => {}
+        other => return Err(format!("Expected the grouped access to fail, got {:?}", other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_or_pattern_bindings() -> TestResult {
+    // The binding comes from the alternative that matched; alternatives that
+    // bind different names are rejected before the match runs
+    let content = r#"
+var got;
+match ([2, 3]) { [a, 1] | [2, a] => got = a; }
+var leaked = "untouched";
+match ([2, 3]) { [leaked, 1] | [2, b] => leaked = "ran"; }
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "or_pattern.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    match interp.get_global("got") {
+        Some(Value::Int(3)) => {}
+        other => return Err(format!("Expected got = 3, got {:?}", other)),
+    }
+    match interp.get_global("leaked") {
+        Some(Value::Str(s)) if s == "untouched" => {}
+        other => return Err(format!("Expected the mismatched match to be rejected, got {:?}", other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_import_module_with_top_level_return() -> TestResult {
+    // A top-level return ends the module early instead of returning from
+    // the function that imported it
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    write_temp_file(&td, "ret.lox", "var early = 1;\nreturn 5;\nvar late = 2;\n")?;
+    let content = r#"
+var after_import = false;
+fun f() { import "ret.lox" as r; after_import = true; return r.early + 10; }
+var result = f();
+import "ret.lox" as again;
+var cached = again.early;
+var late;
+try { late = again.late; } catch (e) { late = e.message; }
+"#;
+    let path = write_temp_file(&td, "main.lox", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    match interp.get_global("after_import") {
+        Some(Value::Bool(true)) => {}
+        other => return Err(format!("Expected f to keep running after the import, got {:?}", other)),
+    }
+    for (name, expected) in [("result", 11), ("cached", 1)] {
+        match interp.get_global(name) {
+            Some(Value::Int(n)) if n == expected => {}
+            other => return Err(format!("Expected {} to be {}, got {:?}", name, expected, other)),
+        }
+    }
+    match interp.get_global("late") {
+        Some(Value::Str(s)) if s == "Undefined property 'late' in namespace 'ret'." => {}
+        other => return Err(format!("Expected 'late' not to be exported, got {:?}", other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn interpret_import_is_silent() -> TestResult {
+    // The importing script's lines are echoed, but the module's aren't, and
+    // its top-level return doesn't print a panic message
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    write_temp_file(&td, "quiet.lox", "var own = 1;\nreturn;\n")?;
+    let path = write_temp_file(&td, "main.lox", "import \"quiet.lox\" as q;\nprint q.own;\n")?;
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_lox_interpreter"))
+        .arg(&path)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| format!("Running the interpreter failed: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !stdout.contains("> import") || !stdout.lines().any(|line| line == "1") {
+        return Err(format!("Expected the script's echo and output, got {:?}", stdout));
+    }
+    if stdout.contains("var own") {
+        return Err(format!("Expected the module not to be echoed, got {:?}", stdout));
+    }
+    if stderr.contains("panicked") {
+        return Err(format!("Expected no panic message, got {:?}", stderr));
+    }
+    Ok(())
+}
@@ -0,0 +1,526 @@
+use lox_interpreter::input::scanner::Scanner;
+use lox_interpreter::parse::parser::Parser;
+use lox_interpreter::interpret::interpreter::Interpreter;
+use lox_interpreter::interpret::value::Value;
+
+use std::fs;
+
+type TestResult = Result<(), String>;
+
+fn write_temp_file(temp_dir: &tempfile::TempDir, filename: &str, content: &str) -> Result<String, String> {
+    let file_path = temp_dir.path().join(filename);
+    fs::write(&file_path, content).map_err(|e| format!("File Write Error: {}", e))?;
+    Ok(file_path.to_str().ok_or_else(|| "Invalid Path".to_string())?.to_string())
+}
+
+// Helper to run a file through parser+interpreter loop (like main())
+fn run_file_and_return_interpreter(path: &str) -> Result<Interpreter, String> {
+    run_file_with_interpreter(path, Interpreter::new())
+}
+
+// Same as above but with a pre-configured interpreter (e.g. custom input).
+fn run_file_with_interpreter(path: &str, mut interpreter: Interpreter) -> Result<Interpreter, String> {
+    let scanner = Scanner::new_from_file(path).map_err(|e| format!("Scanner init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    while !parser.is_at_end() {
+        match parser.parse() {
+            Some(stmt) => interpreter.interpret_stmt(&stmt),
+            None => {
+                if parser.had_error() {
+                    parser.report_errors();
+                    parser.clear_errors();
+                }
+            }
+        }
+    }
+
+    Ok(interpreter)
+}
+
+// Helper to assert that a global holds the expected string.
+fn expect_str(interp: &Interpreter, name: &str, expected: &str) -> TestResult {
+    match interp.get_global(name) {
+        Some(Value::Str(s)) => if s != expected { return Err(format!("Expected {} to be '{}' got '{}'", name, expected, s)); },
+        other => return Err(format!("Expected string global {}, got {:?}", name, other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn native_type_introspection() -> TestResult {
+    let content = r#"
+fun f() {}
+var uninit;
+var t_num = type(1);
+var t_str = type("a");
+var t_bool = type(true);
+var t_nil = type(nil);
+var t_uninit = type(uninit);
+var t_fun = type(f);
+var t_native = type(clock);
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "type.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    expect_str(&interp, "t_num", "number")?;
+    expect_str(&interp, "t_str", "string")?;
+    expect_str(&interp, "t_bool", "bool")?;
+    expect_str(&interp, "t_nil", "nil")?;
+    expect_str(&interp, "t_uninit", "nil")?;
+    expect_str(&interp, "t_fun", "function")?;
+    expect_str(&interp, "t_native", "native")?;
+    Ok(())
+}
+
+#[test]
+fn native_conversions() -> TestResult {
+    let content = r#"
+var s = str(3.0);
+var s_bool = str(false);
+var n = num(" 2.5 ");
+var n_bad = num("abc");
+var b_zero = bool(0);
+var b_nil = bool(nil);
+var n_err = num(true);
+var arity_err = str(1, 2);
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "conv.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    expect_str(&interp, "s", "3")?;
+    expect_str(&interp, "s_bool", "false")?;
+    match interp.get_global("n") {
+        Some(Value::Number(n)) => if (n - 2.5).abs() > f64::EPSILON { return Err(format!("Expected 2.5 got {}", n)); },
+        other => return Err(format!("Expected numeric global n, got {:?}", other)),
+    }
+    match interp.get_global("n_bad") {
+        Some(Value::Nil) => {}
+        other => return Err(format!("Expected nil from num(\"abc\"), got {:?}", other)),
+    }
+    match interp.get_global("b_zero") {
+        Some(Value::Bool(b)) => if !b { return Err("Expected bool(0) to be true".to_string()); },
+        other => return Err(format!("Expected boolean global b_zero, got {:?}", other)),
+    }
+    match interp.get_global("b_nil") {
+        Some(Value::Bool(b)) => if b { return Err("Expected bool(nil) to be false".to_string()); },
+        other => return Err(format!("Expected boolean global b_nil, got {:?}", other)),
+    }
+    // Type and arity errors are runtime errors, so the variables stay unset.
+    if interp.get_global("n_err").is_some() { return Err("Expected 'n_err' to be unset due to type error".to_string()); }
+    if interp.get_global("arity_err").is_some() { return Err("Expected 'arity_err' to be unset due to arity error".to_string()); }
+    Ok(())
+}
+
+#[test]
+fn native_math_namespace() -> TestResult {
+    let content = r#"
+var root = math.sqrt(16);
+var p = math.pow(2, 10);
+var lo = math.min(3, -1);
+var fl = math.floor(-1.5);
+var angle = math.atan2(1, 1) * 4;
+var e = math.E;
+var t_math = type(math);
+var bad_arg = math.sqrt("x");
+var missing = math.nope;
+var sqrt_global = sqrt(4);
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "math.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    let expected = [("root", 4.0), ("p", 1024.0), ("lo", -1.0), ("fl", -2.0), ("angle", std::f64::consts::PI), ("e", std::f64::consts::E)];
+    for (name, want) in expected {
+        match interp.get_global(name) {
+            Some(Value::Number(n)) => if (n - want).abs() > 1e-12 { return Err(format!("Expected {} to be {} got {}", name, want, n)); },
+            other => return Err(format!("Expected numeric global {}, got {:?}", name, other)),
+        }
+    }
+    expect_str(&interp, "t_math", "namespace")?;
+    // Wrong argument types, unknown members and bare globals are all errors.
+    if interp.get_global("bad_arg").is_some() { return Err("Expected 'bad_arg' to be unset due to type error".to_string()); }
+    if interp.get_global("missing").is_some() { return Err("Expected 'missing' to be unset due to undefined property".to_string()); }
+    if interp.get_global("sqrt_global").is_some() { return Err("Expected math natives not to be globals".to_string()); }
+    Ok(())
+}
+
+#[test]
+fn native_string_functions_unicode() -> TestResult {
+    let content = r#"
+var word = "héllo wörld";
+var n = len(word);
+var sub = substr(word, 1, 5);
+var pos = index_of(word, "wörld");
+var missing = index_of(word, "xyz");
+var has = contains(word, "lo w");
+var sw = starts_with(word, "hé");
+var ew = ends_with(word, "ld");
+var up = upper(word);
+var trimmed = trim("  pad  ");
+var replaced = replace("a-b-c", "-", "+");
+var parts = split("a,b,c", ",");
+var part_count = len(parts);
+var second = parts[1];
+var joined = join(parts, " | ");
+var letters = join(split("añb", ""), ".");
+var c = chr(233);
+var code = ord("é");
+var third_char = word[2];
+var out_of_range = substr(word, 3, 99);
+var bad_chr = chr(-1);
+var bad_ord = ord("ab");
+var bad_index = parts[3];
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "strings.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    let numbers = [("n", 11), ("pos", 6), ("missing", -1), ("part_count", 3), ("code", 233)];
+    for (name, want) in numbers {
+        match interp.get_global(name) {
+            Some(Value::Int(n)) => if n != want { return Err(format!("Expected {} to be {} got {}", name, want, n)); },
+            other => return Err(format!("Expected numeric global {}, got {:?}", name, other)),
+        }
+    }
+    for name in ["has", "sw", "ew"] {
+        match interp.get_global(name) {
+            Some(Value::Bool(true)) => {}
+            other => return Err(format!("Expected {} to be true, got {:?}", name, other)),
+        }
+    }
+    expect_str(&interp, "sub", "éllo")?;
+    expect_str(&interp, "up", "HÉLLO WÖRLD")?;
+    expect_str(&interp, "trimmed", "pad")?;
+    expect_str(&interp, "replaced", "a+b+c")?;
+    expect_str(&interp, "second", "b")?;
+    expect_str(&interp, "joined", "a | b | c")?;
+    expect_str(&interp, "letters", "a.ñ.b")?;
+    expect_str(&interp, "c", "é")?;
+    expect_str(&interp, "third_char", "l")?;
+    // Out-of-range arguments are runtime errors, not panics.
+    for name in ["out_of_range", "bad_chr", "bad_ord", "bad_index"] {
+        if interp.get_global(name).is_some() { return Err(format!("Expected '{}' to be unset due to runtime error", name)); }
+    }
+    Ok(())
+}
+
+#[test]
+fn native_console_input_and_eof() -> TestResult {
+    // The script's own source comes from the file; console reads come from
+    // the injected buffer, one line per call, and nil once it runs out.
+    let content = r#"
+var name = input("name? ");
+var second = read_line();
+var after_eof = read_line();
+var greeting = "hi " + name;
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "input.txt", content)?;
+
+    let mut interp = Interpreter::new();
+    interp.set_input(Box::new(std::io::Cursor::new("Ada\r\nline two\n")));
+    let interp = run_file_with_interpreter(&path, interp)?;
+    expect_str(&interp, "name", "Ada")?;
+    expect_str(&interp, "second", "line two")?;
+    expect_str(&interp, "greeting", "hi Ada")?;
+    match interp.get_global("after_eof") {
+        Some(Value::Nil) => {}
+        other => return Err(format!("Expected nil at EOF, got {:?}", other)),
+    }
+    Ok(())
+}
+
+#[test]
+fn native_file_system_opt_in() -> TestResult {
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let dir = td.path().join("data");
+    fs::create_dir(&dir).map_err(|e| format!("mkdir failed: {}", e))?;
+    let dir_s = dir.to_str().ok_or_else(|| "Invalid Path".to_string())?.replace('\\', "/");
+    let content = format!(r#"
+var dir = "{}";
+write_file(dir + "/notes.txt", "one");
+append_file(dir + "/notes.txt", ",two");
+var text = read_file(dir + "/notes.txt");
+var exists = file_exists(dir + "/notes.txt");
+write_file(dir + "/gone.txt", "x");
+remove_file(dir + "/gone.txt");
+var names = join(list_dir(dir), ",");
+var missing = read_file(dir + "/missing.txt");
+"#, dir_s);
+    let path = write_temp_file(&td, "fs.txt", &content)?;
+
+    // Disabled by default: nothing is written and reads fail.
+    let interp = run_file_and_return_interpreter(&path)?;
+    if interp.get_global("text").is_some() { return Err("Expected read_file to fail when file system access is disabled".to_string()); }
+    if dir.join("notes.txt").exists() { return Err("Expected write_file to do nothing when disabled".to_string()); }
+
+    let mut interp = Interpreter::new();
+    interp.set_allow_file_system(true);
+    let interp = run_file_with_interpreter(&path, interp)?;
+    expect_str(&interp, "text", "one,two")?;
+    expect_str(&interp, "names", "notes.txt")?;
+    match interp.get_global("exists") {
+        Some(Value::Bool(true)) => {}
+        other => return Err(format!("Expected exists to be true, got {:?}", other)),
+    }
+    // A missing file is a runtime error carrying the OS message.
+    if interp.get_global("missing").is_some() { return Err("Expected 'missing' to be unset due to I/O error".to_string()); }
+    Ok(())
+}
+
+#[test]
+fn native_random_is_reproducible_when_seeded() -> TestResult {
+    let content = r#"
+seed(42);
+var a1 = random();
+var i1 = random_int(1, 6);
+var xs = [1, 2, 3, 4, 5, 6, 7, 8];
+shuffle(xs);
+var order1 = join(xs, ",");
+
+seed(42);
+var a2 = random();
+var i2 = random_int(1, 6);
+var ys = [1, 2, 3, 4, 5, 6, 7, 8];
+shuffle(ys);
+var order2 = join(ys, ",");
+
+var same = a1 == a2 and i1 == i2 and order1 == order2;
+var in_unit = a1 >= 0 and a1 < 1;
+var in_range = i1 >= 1 and i1 <= 6;
+var bad_bounds = random_int(5, 1);
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "random.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    for name in ["same", "in_unit", "in_range"] {
+        match interp.get_global(name) {
+            Some(Value::Bool(true)) => {}
+            other => return Err(format!("Expected {} to be true, got {:?}", name, other)),
+        }
+    }
+    // Shuffling must keep the same elements.
+    match interp.get_global("xs") {
+        Some(Value::List(items)) => {
+            let mut nums: Vec<i64> = items.borrow().iter().map(|v| match v { Value::Int(n) => *n, _ => -1 }).collect();
+            nums.sort();
+            if nums != vec![1, 2, 3, 4, 5, 6, 7, 8] { return Err(format!("Shuffle changed the elements: {:?}", nums)); }
+        }
+        other => return Err(format!("Expected list global xs, got {:?}", other)),
+    }
+    if interp.get_global("bad_bounds").is_some() { return Err("Expected 'bad_bounds' to be unset due to runtime error".to_string()); }
+    Ok(())
+}
+
+#[test]
+fn native_json_round_trip() -> TestResult {
+    // Lox string literals have no escapes, so the JSON text comes in through
+    // console input.
+    let content = r#"
+var data = json_parse(read_line());
+var name = data.name;
+var second_tag = data["tags"][1];
+var n = data.n;
+var esc = data.esc;
+var none_type = type(data.none);
+var compact = json_stringify(data);
+var fn_err = json_stringify([clock]);
+var parse_err = json_parse("[1, 2,");
+"#;
+    let json_text = r#"{"name": "Zoë", "tags": ["a", "b"], "n": -1.5e2, "ok": true, "none": null, "esc": "\u00e9\ud83d\ude00"}"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "json.txt", content)?;
+
+    let mut interp = Interpreter::new();
+    interp.set_input(Box::new(std::io::Cursor::new(format!("{}\n", json_text))));
+    let interp = run_file_with_interpreter(&path, interp)?;
+    expect_str(&interp, "name", "Zoë")?;
+    expect_str(&interp, "second_tag", "b")?;
+    expect_str(&interp, "esc", "é😀")?;
+    expect_str(&interp, "none_type", "nil")?;
+    match interp.get_global("n") {
+        Some(Value::Number(n)) => if (n + 150.0).abs() > f64::EPSILON { return Err(format!("Expected -150 got {}", n)); },
+        other => return Err(format!("Expected numeric global n, got {:?}", other)),
+    }
+    expect_str(&interp, "compact", "{\"esc\":\"é😀\",\"n\":-150,\"name\":\"Zoë\",\"none\":null,\"ok\":true,\"tags\":[\"a\",\"b\"]}")?;
+    if interp.get_global("fn_err").is_some() { return Err("Expected serializing a function to be a runtime error".to_string()); }
+    if interp.get_global("parse_err").is_some() { return Err("Expected invalid JSON to be a runtime error".to_string()); }
+    Ok(())
+}
+
+#[test]
+fn native_json_errors_and_cycles() -> TestResult {
+    use lox_interpreter::interpret::stdlib::json;
+    use lox_interpreter::token::token::{Token, TokenType};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // Parse errors report the byte offset of the failure.
+    match json::parse("{\"a\": tru}") {
+        Err((msg, offset)) => if offset != 6 { return Err(format!("Expected offset 6 got {} ({})", offset, msg)); },
+        Ok(v) => return Err(format!("Expected parse error, got {:?}", v)),
+    }
+    match json::parse("[1] x") {
+        Err((_, offset)) => if offset != 4 { return Err(format!("Expected offset 4 got {}", offset)); },
+        Ok(v) => return Err(format!("Expected trailing data error, got {:?}", v)),
+    }
+
+    // A list that contains itself cannot be serialized.
+    let mut interp = Interpreter::new();
+    let cyclic = Rc::new(RefCell::new(vec![Value::Number(1.0)]));
+    cyclic.borrow_mut().push(Value::List(cyclic.clone()));
+    let paren = Token::new_token(TokenType::RightParen, ")".to_string(), None, 1);
+    let stringify = match interp.get_global("json_stringify") {
+        Some(Value::Native(n)) => n,
+        other => return Err(format!("Expected native json_stringify, got {:?}", other)),
+    };
+    let result = stringify.call(&mut interp, &paren, &vec![Value::List(cyclic.clone())]);
+    // Break the cycle so the test doesn't leak it.
+    cyclic.borrow_mut().clear();
+    match result {
+        Err(e) => if !e.message.contains("cyclic") { return Err(format!("Expected cyclic error, got {}", e.message)); },
+        Ok(v) => return Err(format!("Expected cyclic structure error, got {:?}", v)),
+    }
+
+    // Pretty printing indents nested containers.
+    let pretty = stringify.call(&mut interp, &paren, &vec![json::parse("[1,{\"k\":[]}]").map_err(|e| e.0)?, Value::Number(2.0)])
+        .map_err(|e| e.message)?;
+    match pretty {
+        Some(Value::Str(s)) => if s != "[\n  1,\n  {\n    \"k\": []\n  }\n]" { return Err(format!("Unexpected pretty output: {}", s)); },
+        other => return Err(format!("Expected string from json_stringify, got {:?}", other)),
+    }
+
+    // Deep nesting is an error rather than a stack overflow, both ways.
+    match json::parse(&"[".repeat(100_000)) {
+        Err((msg, offset)) => if msg != "JSON nested too deeply" || offset != 512 { return Err(format!("Expected depth error at 512, got {} at {}", msg, offset)); },
+        Ok(v) => return Err(format!("Expected depth error, got {:?}", v)),
+    }
+    if json::parse(&format!("{}{}", "[".repeat(512), "]".repeat(512))).is_err() {
+        return Err("Expected 512 levels to parse".to_string());
+    }
+    let mut deep = Value::Nil;
+    for _ in 0..600 {
+        deep = Value::List(Rc::new(RefCell::new(vec![deep])));
+    }
+    match stringify.call(&mut interp, &paren, &vec![deep]) {
+        Err(e) => if e.message != "json_stringify: JSON nested too deeply." { return Err(format!("Expected depth error, got {}", e.message)); },
+        Ok(v) => return Err(format!("Expected depth error, got {:?}", v)),
+    }
+    Ok(())
+}
+
+#[test]
+fn native_assert_and_error() -> TestResult {
+    use lox_interpreter::token::token::{Token, TokenType};
+
+    let content = r#"
+assert(1 < 2);
+assert(true, "never shown");
+assert_eq([1, "a"], [1, "a"]);
+var passed = true;
+var failed_assert = (assert(false, "boom") == nil);
+var failed_eq = (assert_eq(1, 2) == nil);
+var raised = (error("custom") == nil);
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "assert.txt", content)?;
+
+    let mut interp = run_file_and_return_interpreter(&path)?;
+    match interp.get_global("passed") {
+        Some(Value::Bool(true)) => {}
+        other => return Err(format!("Expected passing assertions not to stop the script, got {:?}", other)),
+    }
+    for name in ["failed_assert", "failed_eq", "raised"] {
+        if interp.get_global(name).is_some() { return Err(format!("Expected '{}' to be unset due to runtime error", name)); }
+    }
+
+    // Messages carry the stringified values and the call's line.
+    let paren = Token::new_token(TokenType::RightParen, ")".to_string(), None, 7);
+    let assert_eq = match interp.get_global("assert_eq") {
+        Some(Value::Native(n)) => n,
+        other => return Err(format!("Expected native assert_eq, got {:?}", other)),
+    };
+    match assert_eq.call(&mut interp, &paren, &vec![Value::Number(3.0), Value::Str("3.5".to_string())]) {
+        Err(e) => {
+            if e.message != "assert_eq failed: 3 != 3.5" { return Err(format!("Unexpected message: {}", e.message)); }
+            if e.token.line != 7 { return Err(format!("Expected error at line 7 got {}", e.token.line)); }
+        }
+        Ok(v) => return Err(format!("Expected assert_eq to fail, got {:?}", v)),
+    }
+    Ok(())
+}
+
+#[test]
+fn native_compare_and_sort_use_total_ordering() -> TestResult {
+    let content = r#"
+var xs = ["b", 2, nil, [1, 2], true, "a", 1.5, [1], false, 0 / 0, 10000000000000000000000];
+sort(xs);
+var sorted = str(xs);
+var words = split("pear apple Banana apple", " ");
+sort(words);
+var sorted_words = join(words, ",");
+var c1 = compare(1, 2);
+var c2 = compare("b", "a");
+var c3 = compare(1, 1.0);
+var c4 = compare(nil, false);
+var nan_equal = (0 / 0) == (0 / 0);
+var bad_sort = sort("abc");
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "order.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    expect_str(&interp, "sorted", "[nil, false, true, 1.5, 2, 10000000000000000000000, NaN, \"a\", \"b\", [1], [1, 2]]")?;
+    // Uppercase letters sort before lowercase (character order)
+    expect_str(&interp, "sorted_words", "Banana,apple,apple,pear")?;
+    for (name, want) in [("c1", -1), ("c2", 1), ("c3", 0), ("c4", -1)] {
+        match interp.get_global(name) {
+            Some(Value::Int(n)) => if n != want { return Err(format!("Expected {} = {} got {}", name, want, n)); },
+            other => return Err(format!("Expected int global {}, got {:?}", name, other)),
+        }
+    }
+    match interp.get_global("nan_equal") {
+        Some(Value::Bool(true)) => {}
+        other => return Err(format!("Expected NaN to equal itself, got {:?}", other)),
+    }
+    if interp.get_global("bad_sort").is_some() {
+        return Err("Expected sort of a string to be an error".to_string());
+    }
+    Ok(())
+}
+
+#[test]
+fn native_compare_and_sort_handle_cyclic_lists() -> TestResult {
+    // Distinct lists that contain themselves compare as equal instead of
+    // recursing forever
+    let content = r#"
+var a = [1]; a[0] = a;
+var b = [1]; b[0] = b;
+var same = a == b;
+var less = a < b;
+var ordered = [2, a] > [1, b];
+var xs = [a, [0], b];
+sort(xs);
+var first = xs[0] == [0];
+var c = compare(a, b);
+"#;
+    let td = tempfile::tempdir().map_err(|e| format!("TempDir failed: {}", e))?;
+    let path = write_temp_file(&td, "cyclic_order.txt", content)?;
+
+    let interp = run_file_and_return_interpreter(&path)?;
+    for (name, want) in [("same", true), ("less", false), ("ordered", true), ("first", true)] {
+        match interp.get_global(name) {
+            Some(Value::Bool(b)) if b == want => {}
+            other => return Err(format!("Expected {} = {}, got {:?}", name, want, other)),
+        }
+    }
+    match interp.get_global("c") {
+        Some(Value::Int(0)) => {}
+        other => return Err(format!("Expected compare(a, b) = 0, got {:?}", other)),
+    }
+    Ok(())
+}
@@ -3,6 +3,8 @@ use lox_interpreter::parse::parser::Parser;
 use lox_interpreter::parse::expr::Expr;
 use lox_interpreter::parse::expr::LiteralValue;
 use lox_interpreter::parse::stmt::Stmt;
+use lox_interpreter::parse::pattern::Pattern;
+use lox_interpreter::parse::types::Type;
 use lox_interpreter::token::token::TokenType;
 
 use std::fs;
@@ -33,10 +35,10 @@ fn parser_literal_expression() -> TestResult {
             match expr {
                 Expr::Literal(lit) => {
                     match lit.value {
-                        Some(LiteralValue::Number(n)) => {
-                            if (n - 123.0).abs() > std::f64::EPSILON { return Err(format!("Expected 123.0 got {}", n)); }
+                        Some(LiteralValue::Int(n)) => {
+                            if n != 123 { return Err(format!("Expected 123 got {}", n)); }
                         }
-                        _ => return Err("Expected Number literal".to_string()),
+                        _ => return Err("Expected Int literal".to_string()),
                     }
                 }
                 _ => return Err("Expected literal expression".to_string()),
@@ -68,7 +70,7 @@ fn parser_precedence_expression() -> TestResult {
                 // left should be literal 1
                 if let Expr::Literal(left_lit) = *bin.left {
                     match left_lit.value {
-                        Some(LiteralValue::Number(n)) => if (n - 1.0).abs() > std::f64::EPSILON { return Err(format!("Left literal expected 1, got {}", n)); },
+                        Some(LiteralValue::Int(n)) => if n != 1 { return Err(format!("Left literal expected 1, got {}", n)); },
                         _ => return Err("Left was not a number literal".to_string()),
                     }
                 } else { return Err("Left side not literal".to_string()); }
@@ -78,10 +80,10 @@ fn parser_precedence_expression() -> TestResult {
                     if right_bin.operator.get_type() != TokenType::Star { return Err(format!("Expected * in right subtree, got {:?}", right_bin.operator.get_type())); }
                     // check literals 2 and 3
                     if let Expr::Literal(l2) = *right_bin.left {
-                        match l2.value { Some(LiteralValue::Number(n)) => if (n - 2.0).abs() > std::f64::EPSILON { return Err(format!("Expected 2 got {}", n)); }, _ => return Err("Expected number literal".to_string()) }
+                        match l2.value { Some(LiteralValue::Int(n)) => if n != 2 { return Err(format!("Expected 2 got {}", n)); }, _ => return Err("Expected number literal".to_string()) }
                     } else { return Err("Right.left not literal".to_string()); }
                     if let Expr::Literal(l3) = *right_bin.right {
-                        match l3.value { Some(LiteralValue::Number(n)) => if (n - 3.0).abs() > std::f64::EPSILON { return Err(format!("Expected 3 got {}", n)); }, _ => return Err("Expected number literal".to_string()) }
+                        match l3.value { Some(LiteralValue::Int(n)) => if n != 3 { return Err(format!("Expected 3 got {}", n)); }, _ => return Err("Expected number literal".to_string()) }
                     } else { return Err("Right.right not literal".to_string()); }
                 } else { return Err("Right side not binary".to_string()); }
 
@@ -116,12 +118,12 @@ fn parser_call_expression() -> TestResult {
 
                 // first arg literal 1
                 if let Expr::Literal(l1) = &call.arguments[0] {
-                    match &l1.value { Some(LiteralValue::Number(n)) => if (*n - 1.0).abs() > std::f64::EPSILON { return Err(format!("Expected 1 got {}", n)); }, _ => return Err("Arg1 not number".to_string()) }
+                    match &l1.value { Some(LiteralValue::Int(n)) => if *n != 1 { return Err(format!("Expected 1 got {}", n)); }, _ => return Err("Arg1 not number".to_string()) }
                 } else { return Err("Arg1 not literal".to_string()); }
 
                 // second arg literal 2
                 if let Expr::Literal(l2) = &call.arguments[1] {
-                    match &l2.value { Some(LiteralValue::Number(n)) => if (*n - 2.0).abs() > std::f64::EPSILON { return Err(format!("Expected 2 got {}", n)); }, _ => return Err("Arg2 not number".to_string()) }
+                    match &l2.value { Some(LiteralValue::Int(n)) => if *n != 2 { return Err(format!("Expected 2 got {}", n)); }, _ => return Err("Arg2 not number".to_string()) }
                 } else { return Err("Arg2 not literal".to_string()); }
 
             } else { return Err("Top-level expression not call".to_string()); }
@@ -144,10 +146,10 @@ fn parser_function_declaration() -> TestResult {
     let parsed = parser.parse().ok_or_else(|| "Parser returned None".to_string())?;
 
     match parsed {
-        Stmt::Function { name, params, body } => {
+        Stmt::Function { name, params, body, .. } => {
             if name.lexeme != "f" { return Err(format!("Expected function name f got {}", name.lexeme)); }
             if params.len() != 2 { return Err(format!("Expected 2 params got {}", params.len())); }
-            if params[0].lexeme != "a" || params[1].lexeme != "b" { return Err("Param names mismatch".to_string()); }
+            if params[0].name.lexeme != "a" || params[1].name.lexeme != "b" { return Err("Param names mismatch".to_string()); }
             if body.is_empty() { return Err("Function body empty".to_string()); }
             // first body stmt should be a return
             match &body[0] {
@@ -175,10 +177,10 @@ fn parser_var_declaration() -> TestResult {
     if parser.had_error() { return Err("Parser reported error".to_string()); }
 
     match parsed {
-        Stmt::Var { name, initializer } => {
+        Stmt::Var { name, initializer, .. } => {
             if name.lexeme != "x" { return Err(format!("Expected var name x got {}", name.lexeme)); }
             if let Some(Expr::Literal(lit)) = initializer {
-                if let Some(LiteralValue::Number(n)) = lit.value { if (n - 42.0).abs() > std::f64::EPSILON { return Err(format!("Expected 42 got {}", n)); } }
+                if let Some(LiteralValue::Int(n)) = lit.value { if n != 42 { return Err(format!("Expected 42 got {}", n)); } }
                 else { return Err("Initializer not number literal".to_string()); }
             } else { return Err("Initializer missing or not literal".to_string()); }
         }
@@ -336,7 +338,7 @@ fn parser_function_no_params_empty_body() -> TestResult {
     if parser.had_error() { return Err("Parser reported error".to_string()); }
 
     match parsed {
-        Stmt::Function { name, params, body } => {
+        Stmt::Function { name, params, body, .. } => {
             if name.lexeme != "g" { return Err("Expected function name 'g'".to_string()); }
             if !params.is_empty() { return Err("Expected no params".to_string()); }
             if !body.is_empty() { return Err("Expected empty body".to_string()); }
@@ -346,3 +348,460 @@ fn parser_function_no_params_empty_body() -> TestResult {
 
     Ok(())
 }
+
+#[test]
+fn parser_list_literal_index_and_property() -> TestResult {
+    let content = "[1, \"a\"][0]; math.sqrt(4);\n";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "p11.txt", content)?;
+    let scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    // First stmt: index into a list literal
+    let parsed1 = parser.parse().ok_or_else(|| "Parser returned None for first stmt".to_string())?;
+    if parser.had_error() { return Err("Parser reported error".to_string()); }
+    match parsed1 {
+        Stmt::Expression(Expr::Index(index)) => {
+            match *index.object {
+                Expr::List(list) => if list.elements.len() != 2 { return Err(format!("Expected 2 list elements got {}", list.elements.len())); },
+                _ => return Err("Expected list literal as indexed object".to_string()),
+            }
+        }
+        _ => return Err("Expected index expression statement".to_string()),
+    }
+
+    // Second stmt: call whose callee is a property access
+    let parsed2 = parser.parse().ok_or_else(|| "Parser returned None for second stmt".to_string())?;
+    match parsed2 {
+        Stmt::Expression(Expr::Call(call)) => {
+            match *call.callee {
+                Expr::Get(get) => if get.name.lexeme != "sqrt" { return Err(format!("Expected property 'sqrt' got {}", get.name.lexeme)); },
+                _ => return Err("Expected property access as callee".to_string()),
+            }
+        }
+        _ => return Err("Expected call expression statement".to_string()),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parser_try_catch_finally_and_throw() -> TestResult {
+    let content = "try { throw 1; } catch (e) { } finally { }\ntry { }\n";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "p12.txt", content)?;
+    let scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    let parsed = parser.parse().ok_or_else(|| "Parser returned None".to_string())?;
+    if parser.had_error() { return Err("Parser reported error".to_string()); }
+    match parsed {
+        Stmt::Try { body, catch_name, catch_body, finally_body } => {
+            match body.first() {
+                Some(Stmt::Throw { .. }) => {}
+                _ => return Err("Expected throw statement in try body".to_string()),
+            }
+            match catch_name {
+                Some(tok) => if tok.lexeme != "e" { return Err(format!("Expected catch name 'e' got {}", tok.lexeme)); },
+                None => return Err("Expected catch binding".to_string()),
+            }
+            if catch_body.is_none() || finally_body.is_none() { return Err("Expected catch and finally bodies".to_string()); }
+        }
+        _ => return Err("Expected try statement".to_string()),
+    }
+
+    // A try without catch or finally is a parse error.
+    let second = parser.parse();
+    if second.is_some() || !parser.had_error() { return Err("Expected error for try without catch/finally".to_string()); }
+
+    Ok(())
+}
+
+#[test]
+fn parser_compound_assignment_and_increment() -> TestResult {
+    let content = "xs[0] += 1;\ni++;\n--i;\n1--1;\n1 += 2;\n";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "p13.txt", content)?;
+    let scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    match parser.parse() {
+        Some(Stmt::Expression(Expr::CompoundAssign(assign))) => {
+            if assign.operator.get_type() != TokenType::PlusEqual { return Err("Expected '+=' operator".to_string()); }
+            if !matches!(*assign.target, Expr::Index(_)) { return Err("Expected index target".to_string()); }
+        }
+        _ => return Err("Expected compound assignment statement".to_string()),
+    }
+    match parser.parse() {
+        Some(Stmt::Expression(Expr::Increment(inc))) => {
+            if inc.prefix || inc.operator.get_type() != TokenType::PlusPlus { return Err("Expected postfix '++'".to_string()); }
+        }
+        _ => return Err("Expected postfix increment statement".to_string()),
+    }
+    match parser.parse() {
+        Some(Stmt::Expression(Expr::Increment(inc))) => {
+            if !inc.prefix || inc.operator.get_type() != TokenType::MinusMinus { return Err("Expected prefix '--'".to_string()); }
+        }
+        _ => return Err("Expected prefix decrement statement".to_string()),
+    }
+    // `--` after a literal is a minus and a negation
+    match parser.parse() {
+        Some(Stmt::Expression(Expr::Binary(binary))) => {
+            if binary.operator.get_type() != TokenType::Minus || !matches!(*binary.right, Expr::Unary(_)) { return Err("Expected '1 - (-1)'".to_string()); }
+        }
+        _ => return Err("Expected binary minus".to_string()),
+    }
+    if parser.had_error() { return Err("Parser reported error".to_string()); }
+
+    // Literals are not assignable
+    let bad = parser.parse();
+    if bad.is_some() || !parser.had_error() { return Err("Expected error for '1 += 2'".to_string()); }
+
+    Ok(())
+}
+
+#[test]
+fn parser_const_declaration_and_static_reassignment() -> TestResult {
+    let content = "const LIMIT = 10;\nLIMIT = 11;\n{ var LIMIT = 1; LIMIT = 2; }\nfun f() { LIMIT += 1; }\nconst MISSING;\n";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "p14.txt", content)?;
+    let scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    match parser.parse() {
+        Some(Stmt::Const { name, .. }) => if name.lexeme != "LIMIT" { return Err(format!("Expected const LIMIT got {}", name.lexeme)); },
+        _ => return Err("Expected const declaration".to_string()),
+    }
+
+    // Reassigning the constant is rejected before it runs
+    let reassign = parser.parse();
+    if reassign.is_some() || !parser.had_error() { return Err("Expected error for 'LIMIT = 11'".to_string()); }
+    parser.report_errors();
+    parser.clear_errors();
+
+    // A shadowing var in a block is assignable
+    if parser.parse().is_none() || parser.had_error() { return Err("Expected shadowed assignment to parse".to_string()); }
+
+    // Assignments inside function bodies are checked too
+    let in_function = parser.parse();
+    if in_function.is_some() || !parser.had_error() { return Err("Expected error for 'LIMIT += 1' in function".to_string()); }
+    parser.report_errors();
+    parser.clear_errors();
+
+    // Constants need an initializer
+    let missing = parser.parse();
+    if missing.is_some() || !parser.had_error() { return Err("Expected error for const without initializer".to_string()); }
+
+    Ok(())
+}
+
+#[test]
+fn parser_default_rest_parameters_and_named_arguments() -> TestResult {
+    let content = "fun f(a, b = a + 1, ...rest) { }\nf(1, c: 2);\nf(a: 1, 2);\nfun g(a = 1, b) { }\nfun h(...rest, a) { }\n";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "p15.txt", content)?;
+    let scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    match parser.parse() {
+        Some(Stmt::Function { params, rest, .. }) => {
+            if params.len() != 2 { return Err(format!("Expected 2 params got {}", params.len())); }
+            if params[0].default.is_some() { return Err("Expected 'a' to have no default".to_string()); }
+            if !matches!(params[1].default, Some(Expr::Binary(_))) { return Err("Expected default 'a + 1' for 'b'".to_string()); }
+            match rest {
+                Some(tok) => if tok.lexeme != "rest" { return Err(format!("Expected rest parameter 'rest' got {}", tok.lexeme)); },
+                None => return Err("Expected rest parameter".to_string()),
+            }
+        }
+        _ => return Err("Expected function declaration".to_string()),
+    }
+    match parser.parse() {
+        Some(Stmt::Expression(Expr::Call(call))) => {
+            if call.arguments.len() != 1 { return Err(format!("Expected 1 positional argument got {}", call.arguments.len())); }
+            match call.named.first() {
+                Some((name, _)) if name.lexeme == "c" && call.named.len() == 1 => {}
+                _ => return Err("Expected named argument 'c'".to_string()),
+            }
+        }
+        _ => return Err("Expected call statement".to_string()),
+    }
+    if parser.had_error() { return Err("Parser reported error".to_string()); }
+
+    // A positional argument after a named one, a required parameter after a
+    // default, and a parameter after rest are all errors
+    for case in ["call", "g", "h"] {
+        let bad = parser.parse();
+        if bad.is_some() || !parser.had_error() { return Err(format!("Expected parse error for {}", case)); }
+        parser.report_errors();
+        parser.clear_errors();
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parser_for_in_statement() -> TestResult {
+    let content = "for (x in xs) print x;\nfor (var i in range(3)) { }\nfor (var i = 0; i < 3; i++) { }\n";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "p16.txt", content)?;
+    let scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    for expected in ["x", "i"] {
+        match parser.parse() {
+            Some(Stmt::ForIn { name, iterable, .. }) => {
+                if name.lexeme != expected { return Err(format!("Expected loop variable {} got {}", expected, name.lexeme)); }
+                if expected == "i" && !matches!(iterable, Expr::Call(_)) { return Err("Expected call as iterable".to_string()); }
+            }
+            _ => return Err(format!("Expected for-in statement over {}", expected)),
+        }
+    }
+    // The C-style loop still desugars to a block around a while
+    match parser.parse() {
+        Some(Stmt::Block(stmts)) => if !matches!(stmts.last(), Some(Stmt::While { .. })) { return Err("Expected while loop".to_string()); },
+        _ => return Err("Expected desugared for loop".to_string()),
+    }
+    if parser.had_error() { return Err("Parser reported error".to_string()); }
+    Ok(())
+}
+
+#[test]
+fn parser_generator_functions_and_yield() -> TestResult {
+    let content = "fun* gen(n) { yield n; yield; }\nfun plain() { yield 1; }\nfun* outer() { fun inner() { yield 2; } }\nyield 3;\n";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "p17.txt", content)?;
+    let scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    match parser.parse() {
+        Some(Stmt::Function { name, body, generator, .. }) => {
+            if name.lexeme != "gen" || !generator { return Err("Expected generator function gen".to_string()); }
+            if !matches!(body.as_slice(), [Stmt::Yield { value: Some(_), .. }, Stmt::Yield { value: None, .. }]) {
+                return Err(format!("Expected two yield statements, got {:?}", body));
+            }
+        }
+        _ => return Err("Expected function declaration".to_string()),
+    }
+
+    // yield belongs to the innermost function, which must be a generator
+    for case in ["plain", "inner", "top level"] {
+        let bad = parser.parse();
+        if bad.is_some() || !parser.had_error() { return Err(format!("Expected yield error in {}", case)); }
+        parser.report_errors();
+        parser.clear_errors();
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parser_match_statement_patterns() -> TestResult {
+    let content = "match (v) { 1 | -2 => print 1; [a, _, ...rest] if a > 0 => { } n => print n; }\nmatch (v) { _ => print 1; _ => print 2; }\nmatch (v) { + => print 1; }\n";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "p18.txt", content)?;
+    let scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    match parser.parse() {
+        Some(Stmt::Match { arms, .. }) => {
+            if arms.len() != 3 { return Err(format!("Expected 3 arms got {}", arms.len())); }
+            if !matches!(&arms[0].pattern, Pattern::Or(alts) if alts.len() == 2) { return Err("Expected alternatives in first arm".to_string()); }
+            match &arms[1].pattern {
+                Pattern::List { elements, rest: Some(rest), .. } => {
+                    if elements.len() != 2 || rest.lexeme != "rest" { return Err("Expected [a, _, ...rest]".to_string()); }
+                    if !matches!(elements[1], Pattern::Wildcard(_)) { return Err("Expected wildcard element".to_string()); }
+                }
+                other => return Err(format!("Expected list pattern, got {:?}", other)),
+            }
+            if arms[1].guard.is_none() { return Err("Expected guard on second arm".to_string()); }
+            if !matches!(&arms[2].pattern, Pattern::Binding(name) if name.lexeme == "n") { return Err("Expected binding n".to_string()); }
+        }
+        _ => return Err("Expected match statement".to_string()),
+    }
+    if parser.had_error() { return Err("Parser reported error".to_string()); }
+
+    // Two wildcard arms and a missing pattern are errors
+    for case in ["duplicate wildcard", "missing pattern"] {
+        let bad = parser.parse();
+        if bad.is_some() || !parser.had_error() { return Err(format!("Expected parse error for {}", case)); }
+        parser.report_errors();
+        parser.clear_errors();
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parser_destructuring_declarations_and_assignment() -> TestResult {
+    let content = "var [a, [b, _], ...rest] = xs;\nvar {x, y: [y1]} = point;\n[a, b] = [b, a];\nvar [1, c] = xs;\n[a, 1] = xs;\n";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "p19.txt", content)?;
+    let scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    let names = |pattern: &Pattern| pattern.bindings().iter().map(|t| t.lexeme.clone()).collect::<Vec<_>>().join(",");
+    match parser.parse() {
+        Some(Stmt::VarPattern { pattern, .. }) => if names(&pattern) != "a,b,rest" { return Err(format!("Expected a,b,rest got {}", names(&pattern))); },
+        _ => return Err("Expected list destructuring declaration".to_string()),
+    }
+    match parser.parse() {
+        Some(Stmt::VarPattern { pattern: pattern @ Pattern::Map { .. }, .. }) => if names(&pattern) != "x,y1" { return Err(format!("Expected x,y1 got {}", names(&pattern))); },
+        _ => return Err("Expected map destructuring declaration".to_string()),
+    }
+    match parser.parse() {
+        Some(Stmt::Expression(Expr::DestructureAssign(assign))) => if names(&assign.pattern) != "a,b" { return Err("Expected [a, b] target".to_string()); },
+        _ => return Err("Expected destructuring assignment".to_string()),
+    }
+    if parser.had_error() { return Err("Parser reported error".to_string()); }
+
+    // Literals can't be destructured into
+    for case in ["var [1, c]", "[a, 1] ="] {
+        let bad = parser.parse();
+        if bad.is_some() || !parser.had_error() { return Err(format!("Expected parse error for {}", case)); }
+        parser.report_errors();
+        parser.clear_errors();
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parser_import_statement() -> TestResult {
+    let content = "import \"lib/util.lox\" as util;\nimport util;\n";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "p20.txt", content)?;
+    let scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    match parser.parse() {
+        Some(Stmt::Import { path, name, .. }) => {
+            if path.lexeme != "lib/util.lox" || name.lexeme != "util" { return Err(format!("Unexpected import {} as {}", path.lexeme, name.lexeme)); }
+        }
+        _ => return Err("Expected import statement".to_string()),
+    }
+    // The path must be a string literal
+    let bad = parser.parse();
+    if bad.is_some() || !parser.had_error() { return Err("Expected error for import without a path string".to_string()); }
+    Ok(())
+}
+
+#[test]
+fn parser_type_annotations_and_checker() -> TestResult {
+    let content = "var x: number? = nil;\nfun add(a: number, b: number = 1): number { return a + b; }\nvar s: string = add(1);\nadd(\"one\");\nvar y: list;\n";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "p21.txt", content)?;
+    let scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    match parser.parse() {
+        Some(Stmt::Var { ty: Some(ty), .. }) => if ty != Type::Optional(Box::new(Type::Number)) { return Err(format!("Expected number? got {}", ty)); },
+        _ => return Err("Expected annotated var".to_string()),
+    }
+    match parser.parse() {
+        Some(Stmt::Function { params, return_type, .. }) => {
+            if params[0].ty != Some(Type::Number) || return_type != Some(Type::Number) { return Err("Expected number parameter and return types".to_string()); }
+        }
+        _ => return Err("Expected annotated function".to_string()),
+    }
+
+    // Mismatches against the annotations are parse errors
+    let expected = [
+        "Type mismatch: 's' is declared string but initialized with number.",
+        "Type mismatch: argument 'a' of 'add' expects number but got string.",
+    ];
+    for message in expected {
+        let bad = parser.parse();
+        match parser.first_error() {
+            Some(error) if bad.is_none() && error.ends_with(message) => {}
+            other => return Err(format!("Expected '{}', got {:?}", message, other)),
+        }
+        parser.report_errors();
+        parser.clear_errors();
+    }
+    match parser.parse() {
+        Some(Stmt::Var { ty: Some(Type::List), initializer: None, .. }) => {}
+        _ => return Err("Expected annotated var without initializer".to_string()),
+    }
+    Ok(())
+}
+
+#[test]
+fn parser_checker_rejects_missing_return() -> TestResult {
+    // Falling off the end returns nil, so only types that allow nil may
+    let content = "fun h(): string { }\n\
+fun sign(n: number): string { if (n < 0) return \"-\"; }\n\
+fun pick(n: number): string { if (n < 0) { return \"-\"; } else { return \"+\"; } }\n\
+fun check(n: number): number { match (n) { 0 => return 0; x => throw \"bad\"; } }\n\
+fun spin(): number { while (true) { } }\n\
+fun maybe(): string? { }\n\
+fun* gen(): number { yield 1; }\n";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "p24.txt", content)?;
+    let scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    for message in [
+        "Error at 'h': Type mismatch: 'h' returns string but can reach the end without returning.",
+        "Error at 'sign': Type mismatch: 'sign' returns string but can reach the end without returning.",
+    ] {
+        let bad = parser.parse();
+        match parser.first_error() {
+            Some(error) if bad.is_none() && error.ends_with(message) => {}
+            other => return Err(format!("Expected '{}', got {:?}", message, other)),
+        }
+        parser.report_errors();
+        parser.clear_errors();
+    }
+    for name in ["pick", "check", "spin", "maybe", "gen"] {
+        match parser.parse() {
+            Some(Stmt::Function { name: found, .. }) if found.lexeme == name => {}
+            _ => return Err(format!("Expected '{}' to pass, got {:?}", name, parser.first_error())),
+        }
+    }
+    if parser.had_error() { return Err("Parser reported error".to_string()); }
+    Ok(())
+}
+
+#[test]
+fn parser_nil_coalescing_and_optional_chaining() -> TestResult {
+    let content = "a or b ?? c;\nobj?.field;\nf?.(1);\n";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "p22.txt", content)?;
+    let scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    // `??` binds looser than `or`
+    match parser.parse() {
+        Some(Stmt::Expression(Expr::Logical(outer))) => {
+            if outer.operator.get_type() != TokenType::QuestionQuestion { return Err(format!("Expected ?? at the top, got {}", outer.operator.lexeme)); }
+            if !matches!(*outer.left, Expr::Logical(ref inner) if inner.operator.get_type() == TokenType::Or) { return Err("Expected 'a or b' on the left".to_string()); }
+        }
+        _ => return Err("Expected logical expression".to_string()),
+    }
+    match parser.parse() {
+        Some(Stmt::Expression(Expr::Get(get))) => if !get.optional || get.name.lexeme != "field" { return Err("Expected optional property access".to_string()); },
+        _ => return Err("Expected property access".to_string()),
+    }
+    match parser.parse() {
+        Some(Stmt::Expression(Expr::Call(call))) => if !call.optional || call.arguments.len() != 1 { return Err("Expected optional call with one argument".to_string()); },
+        _ => return Err("Expected call".to_string()),
+    }
+    if parser.had_error() { return Err("Parser reported error".to_string()); }
+    Ok(())
+}
+
+#[test]
+fn parser_rejects_double_dot() -> TestResult {
+    let content = "s..s;\n";
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("TempDir creation failed: {}", e))?;
+    let path = write_temp_file(&temp_dir, "p23.txt", content)?;
+    let scanner = Scanner::new_from_file(&path).map_err(|e| format!("Reader init failed: {}", e))?;
+    let mut parser = Parser::new(scanner);
+
+    let bad = parser.parse();
+    match parser.first_error() {
+        Some(error) if bad.is_none() && error.ends_with("Error at '..': Unexpected '..'.") => {}
+        other => return Err(format!("Expected error for '..', got {:?}", other)),
+    }
+    Ok(())
+}
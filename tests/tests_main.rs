@@ -10,4 +10,5 @@ mod parser {
 }
 mod interpret {
     mod interpreter_test;
+    mod native_test;
 }
\ No newline at end of file